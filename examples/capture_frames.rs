@@ -0,0 +1,50 @@
+extern crate decklink;
+
+use decklink::device::get_devices;
+use decklink::device::input::DecklinkVideoInputFlags;
+use decklink::display_mode::DecklinkDisplayModeId;
+use decklink::frame::{DecklinkFrameBase, DecklinkPixelFormat};
+
+fn main() {
+    let devices = get_devices()
+        .expect("Unable to list Decklink devices. The Decklink drivers may not be insalled.");
+
+    let input = devices
+        .iter()
+        .find_map(|d| d.input())
+        .expect("No Decklink device with an input was found");
+
+    input
+        .enable_video_input(
+            DecklinkDisplayModeId::HD1080i50,
+            DecklinkPixelFormat::Format8BitYUV,
+            DecklinkVideoInputFlags::empty(),
+        )
+        .expect("Failed to enable video input");
+
+    let mut frames = input.frames(8).expect("Failed to create the capture queue");
+    input.start_streams().expect("Failed to start streams");
+
+    for frame in frames.by_ref().take(250) {
+        if let Some(video) = frame.video {
+            let time = video.stream_time(25).map(|(time, _)| time).unwrap_or(-1);
+            println!(
+                "Frame {}: {}x{} {:?}",
+                time,
+                video.width(),
+                video.height(),
+                video.flags()
+            );
+        }
+    }
+
+    println!(
+        "Received {} frames, dropped {}",
+        frames.received_count(),
+        frames.dropped_count()
+    );
+
+    // Release the queue before the device, so that the callback is not left waiting on it
+    drop(frames);
+    let _ = input.stop_streams();
+}
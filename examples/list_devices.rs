@@ -2,6 +2,7 @@ extern crate decklink;
 
 use decklink::api_version;
 use decklink::connectors::DecklinkVideoConnection;
+use decklink::device::input::DecklinkVideoInputFlags;
 use decklink::device::output::DecklinkVideoOutputFlags;
 use decklink::device::DecklinkDisplayModeSupport;
 use decklink::device::{get_devices, DecklinkDevice, DecklinkDeviceDisplayModes};
@@ -142,8 +143,20 @@ fn print_modes<T>(
     dev: &dyn DecklinkDeviceDisplayModes<T>,
     flags: T,
 ) where
-    T: Copy,
+    T: Copy + PartialEq,
 {
+    // Only the flags being listed are relevant, so there is no need to probe the others
+    let matrix = match dev.capability_matrix_for(&[flags]) {
+        Ok(matrix) => matrix,
+        Err(e) => {
+            println!(
+                "Could not probe the supported pixel formats - result = {:?}",
+                e
+            );
+            return;
+        }
+    };
+
     for mode in modes {
         let name = mode.name().unwrap_or_else(|| "Unknown".to_string());
         let width = mode.width();
@@ -160,15 +173,7 @@ fn print_modes<T>(
         );
 
         for format in DecklinkPixelFormat::iter() {
-            let supported = {
-                let v = dev.does_support_video_mode(mode.mode(), format, flags);
-                match v {
-                    Err(_) => DecklinkDisplayModeSupport::NotSupported,
-                    Ok(v) => v.0,
-                }
-            };
-
-            if supported == DecklinkDisplayModeSupport::NotSupported {
+            if matrix.get(mode.mode(), format, flags) == DecklinkDisplayModeSupport::NotSupported {
                 print!("------\t\t");
             } else {
                 print!("{:?}\t", format);
@@ -193,18 +198,18 @@ fn print_output_modes(device: &DecklinkDevice) {
     }
 }
 
-fn print_input_modes(_device: &DecklinkDevice) {
-    //    if let Some(input) = device.input() {
-    //        if let Ok(modes) = output.display_modes() {
-    //            println!("Supported video input display modes and pixel formats:");
-    //
-    //            print_modes(modes, &input, DecklinkVideoInputFlags::empty());
-    //        } else {
-    //            println!("Could not obtain supported display mode list");
-    //        }
-    //    } else {
-    //        println!("Could not obtain the device input");
-    //    }
+fn print_input_modes(device: &DecklinkDevice) {
+    if let Some(input) = device.input() {
+        if let Ok(modes) = input.display_modes() {
+            println!("Supported video input display modes and pixel formats:");
+
+            print_modes(modes, &input, DecklinkVideoInputFlags::empty());
+        } else {
+            println!("Could not obtain supported display mode list");
+        }
+    } else {
+        println!("Could not obtain the device input");
+    }
 }
 
 fn print_capabilities(device: &DecklinkDevice) {
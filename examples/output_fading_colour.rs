@@ -2,19 +2,17 @@ extern crate decklink;
 #[macro_use]
 extern crate text_io;
 
-use decklink::device::output::DecklinkVideoOutputFlags;
 use decklink::device::output::{
-    DeckLinkVideoOutputCallback, DecklinkOutputDevice, DecklinkOutputFrameCompletionResult,
+    DecklinkOutputDevice, DecklinkVideoOutputFlags, FrameSource, OutputStatsCollector,
+    ScheduledPlayout, ScheduledPlayoutOptions,
 };
 use decklink::device::DecklinkDeviceDisplayModes;
 use decklink::device::{get_devices, DecklinkDevice};
 use decklink::display_mode::DecklinkDisplayMode;
 use decklink::frame::{
-    DecklinkFrameFlags, DecklinkPixelFormat, DecklinkVideoFrame, DecklinkVideoMutableFrame,
+    DecklinkFrameBase, DecklinkFrameFlags, DecklinkPixelFormat, DecklinkVideoMutableFrame,
 };
 use std::sync::Arc;
-use std::thread::sleep;
-use std::time::Duration;
 
 fn select_output_and_format() -> Option<(DecklinkDevice, DecklinkOutputDevice, DecklinkDisplayMode)>
 {
@@ -81,77 +79,67 @@ fn select_output_and_format() -> Option<(DecklinkDevice, DecklinkOutputDevice, D
     Some((device, output, mode))
 }
 
-struct CompletionCallback {}
-impl DeckLinkVideoOutputCallback for CompletionCallback {
-    fn schedule_frame_completed_callback(
-        &self,
-        _frame: Option<DecklinkVideoFrame>,
-        _result: DecklinkOutputFrameCompletionResult,
-    ) -> bool {
-        println!("Frame completed");
-        sleep(Duration::from_millis(100));
-        return true;
-    }
-    fn playback_stopped(&self) -> bool {
-        println!("Playback stopped");
-        return true;
-    }
+struct FadingColourSource {
+    width: usize,
+    height: usize,
 }
-
-fn main() {
-    if let Some((_device, output, mode)) = select_output_and_format() {
+impl FrameSource for FadingColourSource {
+    fn next_video_frame(&mut self, frame_number: i64) -> Option<Box<dyn DecklinkFrameBase + Send>> {
         let mut frame = Box::new(DecklinkVideoMutableFrame::create(
-            mode.width(),
-            mode.height(),
-            mode.width() * 4,
+            self.width,
+            self.height,
+            self.width * 4,
             DecklinkPixelFormat::Format8BitBGRA,
             DecklinkFrameFlags::empty(),
         ));
 
-        let bytes = vec![120u8; (mode.width() * mode.height() * 4) as usize];
-        if frame.copy_bytes(&bytes).is_err() {
-            println!("Failed to set frame bytes");
-            return;
-        }
+        // Fade down and back up over 510 frames
+        let level = (frame_number % 510 - 255).unsigned_abs() as u8;
+        let bytes = vec![level; self.width * self.height * 4];
+        frame.copy_bytes(&bytes).ok()?;
 
-        {
-            let mut video_output = output
-                .enable_video_output_scheduled(
-                    mode.mode(),
-                    DecklinkVideoOutputFlags::empty(),
-                    25000,
-                )
-                .expect("Failed to enable video output");
-
-            video_output
-                .schedule_frame_copy(frame.as_ref(), 1000, 1000)
-                .expect("Failed to schedule frame");
-            video_output
-                .schedule_frame_copy(frame.as_ref(), 2000, 1000)
-                .expect("Failed to schedule frame");
-            video_output
-                .schedule_frame_copy(frame.as_ref(), 200000000, 1000)
-                .expect("Failed to schedule frame");
-            video_output
-                .schedule_frame_copy(frame.as_ref(), 300000000, 1000)
-                .expect("Failed to schedule frame");
-            video_output
-                .schedule_frame_copy(frame.as_ref(), 400000000, 1000)
-                .expect("Failed to schedule frame");
-
-            let handler = Arc::new(CompletionCallback {});
-            video_output
-                .set_callback(Some(handler.clone()))
-                .expect("Failed to set callback");
-
-            video_output
-                .start_playback(0, 1.0)
-                .expect("Playback to start");
-
-            println!("Press enter to continue");
-            let _s: String = read!();
-
-            // All done
-        }
+        Some(frame)
+    }
+}
+
+fn main() {
+    if let Some((_device, output, mode)) = select_output_and_format() {
+        let (frame_duration, timescale) = mode.framerate().expect("Failed to get framerate");
+
+        let video_output = output
+            .enable_video_output_scheduled(
+                mode.mode(),
+                DecklinkVideoOutputFlags::empty(),
+                timescale,
+            )
+            .expect("Failed to enable video output");
+
+        let source = Box::new(FadingColourSource {
+            width: mode.width(),
+            height: mode.height(),
+        });
+
+        let playout = ScheduledPlayout::new(
+            video_output,
+            None,
+            source,
+            frame_duration,
+            ScheduledPlayoutOptions::default(),
+        )
+        .expect("Failed to create playout");
+
+        let stats = Arc::new(OutputStatsCollector::new(frame_duration));
+        playout
+            .set_stats(Some(stats.clone()))
+            .expect("Failed to attach stats");
+
+        playout.start().expect("Playback to start");
+
+        println!("Press enter to continue");
+        let _s: String = read!();
+
+        println!("{:?}", stats.snapshot());
+
+        // All done
     }
 }
@@ -0,0 +1,76 @@
+extern crate decklink;
+#[macro_use]
+extern crate text_io;
+
+use decklink::device::get_devices;
+use decklink::device::notification::{
+    DeckLinkNotificationCallback, DecklinkDeviceNotificationExt, NotificationTopic,
+};
+use decklink::device::status::{DecklinkDeviceStatus, DecklinkStatusId};
+use std::sync::Arc;
+
+struct SignalCallback {
+    name: String,
+    status: DecklinkDeviceStatus,
+}
+impl DeckLinkNotificationCallback for SignalCallback {
+    fn notify_status(&self, id: DecklinkStatusId) -> bool {
+        match id {
+            DecklinkStatusId::VideoInputSignalLocked => {
+                match self.status.video_input_signal_locked() {
+                    Ok(true) => println!("{}: Input signal locked", self.name),
+                    Ok(false) => println!("{}: Input signal lost", self.name),
+                    Err(e) => println!("{}: Failed to read input signal: {:?}", self.name, e),
+                }
+            }
+            DecklinkStatusId::DetectedVideoInputMode => {
+                if let Ok(mode) = self.status.detected_video_input_mode() {
+                    println!("{}: Input mode changed to {:?}", self.name, mode);
+                }
+            }
+            _ => {}
+        }
+        true
+    }
+}
+
+fn main() {
+    let devices = get_devices()
+        .expect("Unable to list Decklink devices. The Decklink drivers may not be insalled.");
+
+    // The subscriptions are only valid for as long as the handles are alive
+    let mut handles = Vec::new();
+    for device in &devices {
+        let name = device
+            .display_name()
+            .unwrap_or_else(|| "Unknown".to_string());
+
+        let status = match device.get_status() {
+            Ok(status) => status,
+            Err(e) => {
+                println!("{}: Could not obtain status object: {:?}", name, e);
+                continue;
+            }
+        };
+        let notification = match device.get_notification() {
+            Ok(notification) => notification,
+            Err(e) => {
+                println!("{}: Could not obtain notification object: {:?}", name, e);
+                continue;
+            }
+        };
+
+        println!("{}: Monitoring input signal", name);
+
+        let callback = Arc::new(SignalCallback { name, status });
+        let handle = notification
+            .subscribe(NotificationTopic::StatusChanged, callback)
+            .expect("Failed to subscribe to notifications");
+        handles.push(handle);
+    }
+
+    println!("Press enter to continue");
+    let _s: String = read!();
+
+    // Cleanup happens during object destruction
+}
@@ -17,6 +17,11 @@ impl DeckLinkNotificationCallback for NotificationCallback {
         print_status(&self.status, id);
         true
     }
+
+    fn notify_preferences_changed(&self) -> bool {
+        println!("Preferences changed");
+        true
+    }
 }
 
 fn print_status(status: &DecklinkDeviceStatus, id: DecklinkStatusId) {
@@ -28,97 +33,8 @@ fn print_status(status: &DecklinkDeviceStatus, id: DecklinkStatusId) {
      * A device that doesn't support automatic mode detection will fail
      * a request for DecklinkStatusId::DetectedVideoInputMode.
      */
-    match id {
-        DecklinkStatusId::DetectedVideoInputMode => {
-            if let Ok(value) = status.detected_video_input_mode() {
-                print_line(id, format!("{:?}", value))
-            }
-        }
-        DecklinkStatusId::DetectedVideoInputFlags => {
-            if let Ok(value) = status.detected_video_input_flags() {
-                print_line(id, format!("{:08X}", value))
-            }
-        }
-        DecklinkStatusId::CurrentVideoInputMode => {
-            if let Ok(value) = status.current_video_input_mode() {
-                print_line(id, format!("{:?}", value))
-            }
-        }
-        DecklinkStatusId::CurrentVideoInputPixelFormat => {
-            if let Ok(value) = status.current_video_input_pixel_format() {
-                print_line(id, format!("{:?}", value))
-            }
-        }
-        DecklinkStatusId::CurrentVideoInputFlags => {
-            if let Ok(value) = status.current_video_input_flags() {
-                print_line(id, format!("{:08X}", value))
-            }
-        }
-        DecklinkStatusId::CurrentVideoOutputMode => {
-            if let Ok(value) = status.current_video_output_mode() {
-                print_line(id, format!("{:?}", value))
-            }
-        }
-        DecklinkStatusId::CurrentVideoOutputFlags => {
-            if let Ok(value) = status.current_video_output_flags() {
-                print_line(id, format!("{:08X}", value))
-            }
-        }
-        DecklinkStatusId::PCIExpressLinkWidth => {
-            if let Ok(value) = status.pci_express_link_width() {
-                print_line(id, format!("{:X}", value))
-            }
-        }
-        DecklinkStatusId::PCIExpressLinkSpeed => {
-            if let Ok(value) = status.pci_express_link_speed() {
-                print_line(id, format!("{}", value))
-            }
-        }
-        DecklinkStatusId::LastVideoOutputPixelFormat => {
-            if let Ok(value) = status.last_video_output_pixel_format() {
-                print_line(id, format!("{:?}", value))
-            }
-        }
-        DecklinkStatusId::ReferenceSignalMode => {
-            if let Ok(value) = status.reference_signal_mode() {
-                print_line(id, format!("{:?}", value))
-            }
-        }
-        DecklinkStatusId::ReferenceSignalFlags => {
-            if let Ok(value) = status.reference_signal_flags() {
-                print_line(id, format!("{:08X}", value))
-            }
-        }
-        DecklinkStatusId::DuplexMode => {
-            if let Ok(value) = status.duplex_mode() {
-                print_line(id, format!("{:?}", value))
-            }
-        }
-        DecklinkStatusId::Busy => {
-            if let Ok(value) = status.busy() {
-                print_line(id, format!("{:08X}", value))
-            }
-        }
-        DecklinkStatusId::InterchangeablePanelType => {
-            if let Ok(value) = status.interchangeable_panel_type() {
-                print_line(id, format!("{:08X}", value))
-            }
-        }
-        DecklinkStatusId::VideoInputSignalLocked => {
-            if let Ok(value) = status.video_input_signal_locked() {
-                print_line(id, format!("{:?}", value))
-            }
-        }
-        DecklinkStatusId::ReferenceSignalLocked => {
-            if let Ok(value) = status.reference_signal_locked() {
-                print_line(id, format!("{:?}", value))
-            }
-        }
-        DecklinkStatusId::ReceivedEDID => {
-            if let Ok(value) = status.received_edid() {
-                print_line(id, format!("{:?}", value))
-            }
-        }
+    if let Ok(value) = status.get(id) {
+        print_line(id, format!("{:?}", value))
     }
 }
 
@@ -173,6 +89,9 @@ fn main() {
     let _handle = notification
         .subscribe(NotificationTopic::StatusChanged, callback.clone())
         .expect("Failed to subscribe to notifications");
+    let _preferences_handle = notification
+        .subscribe(NotificationTopic::PreferencesChanged, callback.clone())
+        .expect("Failed to subscribe to notifications");
 
     println!("Press enter to continue");
     let _s: String = read!();
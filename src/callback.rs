@@ -0,0 +1,64 @@
+use crate::{sdk, SdkError};
+use std::any::Any;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::RwLock;
+
+/// Details of a panic that was caught in a callback from the decklink driver
+#[derive(Debug, Clone)]
+pub struct CallbackPanic {
+    /// The name of the callback that panicked, such as `video_input_frame_arrived`
+    pub callback: &'static str,
+    /// The panic message, if it was a string
+    pub message: Option<String>,
+}
+
+/// A function to report panics caught in callbacks
+pub type CallbackPanicHook = Box<dyn Fn(&CallbackPanic) + Send + Sync>;
+
+static PANIC_HOOK: RwLock<Option<CallbackPanicHook>> = RwLock::new(None);
+
+/// Set the function that is called when a callback panics.
+/// A panic must not unwind into the driver, so it is caught and the callback reports a failure to the driver instead.
+/// When no hook is set the panic is only reported by the standard panic hook, as for any other panic.
+pub fn set_callback_panic_hook(hook: Option<CallbackPanicHook>) {
+    let mut current = match PANIC_HOOK.write() {
+        Ok(current) => current,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    *current = hook;
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> Option<String> {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        Some(s.to_string())
+    } else {
+        payload.downcast_ref::<String>().cloned()
+    }
+}
+
+fn report_panic(callback: &'static str, payload: Box<dyn Any + Send>) {
+    let hook = match PANIC_HOOK.read() {
+        Ok(hook) => hook,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    if let Some(hook) = &*hook {
+        let info = CallbackPanic {
+            callback,
+            message: panic_message(payload.as_ref()),
+        };
+        // The hook is user code too, so must not unwind either
+        let _ = catch_unwind(AssertUnwindSafe(|| hook(&info)));
+    }
+}
+
+/// Run the body of a callback from the driver, and convert the result to be returned to the driver
+pub(crate) fn run_callback<F: FnOnce() -> bool>(callback: &'static str, f: F) -> sdk::HRESULT {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(true) => 0,  // Ok
+        Ok(false) => 1, // False
+        Err(payload) => {
+            report_panic(callback, payload);
+            SdkError::FAIL as sdk::HRESULT
+        }
+    }
+}
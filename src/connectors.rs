@@ -2,6 +2,7 @@ use crate::sdk;
 
 bitflags! {
     #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct DecklinkVideoConnection: u32 {
         const SDI = sdk::_DecklinkVideoConnection_decklinkVideoConnectionSDI;
         const HDMI = sdk::_DecklinkVideoConnection_decklinkVideoConnectionHDMI;
@@ -14,6 +15,7 @@ bitflags! {
 
 bitflags! {
     #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct DecklinkAudioConnection: u32 {
         const EMBEDDED = sdk::_DecklinkAudioConnection_decklinkAudioConnectionEmbedded;
         const AES_EBU = sdk::_DecklinkAudioConnection_decklinkAudioConnectionAESEBU;
@@ -27,6 +29,7 @@ bitflags! {
 
 bitflags! {
     #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct DecklinkDeckControlConnection: u32 {
         const RS422_REMOTE_1 = sdk::_DecklinkDeckControlConnection_decklinkDeckControlConnectionRS422Remote1;
         const RS422_REMOTE_2 = sdk::_DecklinkDeckControlConnection_decklinkDeckControlConnectionRS422Remote2;
@@ -6,6 +6,109 @@ use crate::util::{convert_and_release_c_string, convert_c_string};
 use crate::{sdk, SdkError};
 use std::ptr::{null, null_mut};
 
+bitflags! {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct DecklinkVideoIOSupport: u32 {
+        const CAPTURE = sdk::_DecklinkVideoIOSupport_decklinkDeviceSupportsCapture;
+        const PLAYBACK = sdk::_DecklinkVideoIOSupport_decklinkDeviceSupportsPlayback;
+    }
+}
+
+#[derive(PartialEq, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DecklinkDeviceInterface {
+    Pci,
+    Usb,
+    Thunderbolt,
+    /// An interface not known to this version of the bindings
+    Other(u32),
+}
+impl From<u32> for DecklinkDeviceInterface {
+    fn from(value: u32) -> Self {
+        match value {
+            sdk::_DecklinkDeviceInterface_decklinkDeviceInterfacePCI => Self::Pci,
+            sdk::_DecklinkDeviceInterface_decklinkDeviceInterfaceUSB => Self::Usb,
+            sdk::_DecklinkDeviceInterface_decklinkDeviceInterfaceThunderbolt => Self::Thunderbolt,
+            v => Self::Other(v),
+        }
+    }
+}
+impl From<DecklinkDeviceInterface> for u32 {
+    fn from(value: DecklinkDeviceInterface) -> Self {
+        match value {
+            DecklinkDeviceInterface::Pci => {
+                sdk::_DecklinkDeviceInterface_decklinkDeviceInterfacePCI
+            }
+            DecklinkDeviceInterface::Usb => {
+                sdk::_DecklinkDeviceInterface_decklinkDeviceInterfaceUSB
+            }
+            DecklinkDeviceInterface::Thunderbolt => {
+                sdk::_DecklinkDeviceInterface_decklinkDeviceInterfaceThunderbolt
+            }
+            DecklinkDeviceInterface::Other(v) => v,
+        }
+    }
+}
+
+/// A copy of every attribute of a device.
+/// Each value is `None` if it is not supported by the device.
+#[derive(PartialEq, Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AttributesSnapshot {
+    pub supports_internal_keying: Option<bool>,
+    pub supports_external_keying: Option<bool>,
+    pub supports_hd_keying: Option<bool>,
+    pub supports_input_format_detection: Option<bool>,
+    pub has_reference_input: Option<bool>,
+    pub has_serial_port: Option<bool>,
+    pub has_analog_video_output_gain: Option<bool>,
+    pub can_only_adjust_overall_video_output_gain: Option<bool>,
+    pub has_video_input_anti_aliasing_filter: Option<bool>,
+    pub has_bypass: Option<bool>,
+    pub supports_clock_timing_adjustment: Option<bool>,
+    pub supports_full_duplex: Option<bool>,
+    pub supports_full_frame_reference_input_timing_offset: Option<bool>,
+    pub supports_smpte_level_a_output: Option<bool>,
+    pub supports_dual_link_sdi: Option<bool>,
+    pub supports_quad_link_sdi: Option<bool>,
+    pub supports_idle_output: Option<bool>,
+    pub has_ltc_timecode_input: Option<bool>,
+    pub supports_duplex_mode_configuration: Option<bool>,
+    pub supports_hdr_metadata: Option<bool>,
+    pub maximum_audio_channels: Option<i64>,
+    pub maximum_analog_audio_input_channels: Option<i64>,
+    pub maximum_analog_audio_output_channels: Option<i64>,
+    pub number_of_sub_devices: Option<i64>,
+    pub sub_device_index: Option<i64>,
+    pub persistent_id: Option<i64>,
+    pub device_group_id: Option<i64>,
+    pub topological_id: Option<i64>,
+    pub video_output_connections: Option<DecklinkVideoConnection>,
+    pub video_input_connections: Option<DecklinkVideoConnection>,
+    pub audio_output_connections: Option<DecklinkAudioConnection>,
+    pub audio_input_connections: Option<DecklinkAudioConnection>,
+    pub video_io_support: Option<DecklinkVideoIOSupport>,
+    pub deck_control_connections: Option<DecklinkDeckControlConnection>,
+    pub device_interface: Option<DecklinkDeviceInterface>,
+    pub audio_input_rca_channel_count: Option<i64>,
+    pub audio_input_xlr_channel_count: Option<i64>,
+    pub audio_output_rca_channel_count: Option<i64>,
+    pub audio_output_xlr_channel_count: Option<i64>,
+    pub paired_device_persistent_id: Option<i64>,
+    pub video_input_gain_minimum: Option<f64>,
+    pub video_input_gain_maximum: Option<f64>,
+    pub video_output_gain_minimum: Option<f64>,
+    pub video_output_gain_maximum: Option<f64>,
+    pub microphone_input_gain_minimum: Option<f64>,
+    pub microphone_input_gain_maximum: Option<f64>,
+    pub serial_port_device_name: Option<String>,
+    pub vendor_name: Option<String>,
+    pub display_name: Option<String>,
+    pub model_name: Option<String>,
+    pub device_handle: Option<String>,
+}
+
 pub struct DecklinkDeviceAttributes {
     dev: *mut sdk::cdecklink_attributes_t,
 }
@@ -19,7 +122,15 @@ impl Drop for DecklinkDeviceAttributes {
     }
 }
 
+impl Clone for DecklinkDeviceAttributes {
+    fn clone(&self) -> Self {
+        unsafe { sdk::cdecklink_attributes_add_ref(self.dev) };
+        DecklinkDeviceAttributes { dev: self.dev }
+    }
+}
+
 impl DecklinkDeviceAttributes {
+    /// Wrap a raw pointer. This takes ownership of the reference returned by the query
     pub(crate) fn from(ptr: *mut sdk::cdecklink_attributes_t) -> DecklinkDeviceAttributes {
         DecklinkDeviceAttributes { dev: ptr }
     }
@@ -207,9 +318,9 @@ impl DecklinkDeviceAttributes {
     }
     /// The capture and/or playback capability of the device.
     /// (See BMDVideoIOSupport for more information)
-    pub fn video_io_support(&self) -> Result<i64, SdkError> {
-        // TODO - return BMDVideoIOSupport
+    pub fn video_io_support(&self) -> Result<DecklinkVideoIOSupport, SdkError> {
         self.get_int(sdk::_DecklinkAttributeID_decklinkVideoIOSupport)
+            .map(|v| DecklinkVideoIOSupport::from_bits_retain(v as u32))
     }
     /// The deck control connections supported by the hardware
     /// (see BMDDeckControlConnection for more information).
@@ -219,9 +330,9 @@ impl DecklinkDeviceAttributes {
     }
     /// The active device interface
     /// (see BMDDeviceInterface for more information)
-    pub fn device_interface(&self) -> Result<i64, SdkError> {
-        // TODO - return BMDDeviceInterface
+    pub fn device_interface(&self) -> Result<DecklinkDeviceInterface, SdkError> {
         self.get_int(sdk::_DecklinkAttributeID_decklinkDeviceInterface)
+            .map(|v| DecklinkDeviceInterface::from(v as u32))
     }
     /// Number of input audio RCA channels supported by this device.
     pub fn audio_input_rca_channel_count(&self) -> Result<i64, SdkError> {
@@ -291,4 +402,91 @@ impl DecklinkDeviceAttributes {
     pub fn device_handle(&self) -> Result<String, SdkError> {
         self.get_string_pointer(sdk::_DecklinkAttributeID_decklinkDeviceHandle)
     }
+
+    /// Read every attribute of the device.
+    /// Values which are not supported by the device are `None`.
+    pub fn snapshot(&self) -> AttributesSnapshot {
+        AttributesSnapshot {
+            supports_internal_keying: self.supports_internal_keying().ok(),
+            supports_external_keying: self.supports_external_keying().ok(),
+            supports_hd_keying: self.supports_hd_keying().ok(),
+            supports_input_format_detection: self.supports_input_format_detection().ok(),
+            has_reference_input: self.has_reference_input().ok(),
+            has_serial_port: self.has_serial_port().ok(),
+            has_analog_video_output_gain: self.has_analog_video_output_gain().ok(),
+            can_only_adjust_overall_video_output_gain: self
+                .can_only_adjust_overall_video_output_gain()
+                .ok(),
+            has_video_input_anti_aliasing_filter: self.has_video_input_anti_aliasing_filter().ok(),
+            has_bypass: self.has_bypass().ok(),
+            supports_clock_timing_adjustment: self.supports_clock_timing_adjustment().ok(),
+            supports_full_duplex: self.supports_full_duplex().ok(),
+            supports_full_frame_reference_input_timing_offset: self
+                .supports_full_frame_reference_input_timing_offset()
+                .ok(),
+            supports_smpte_level_a_output: self.supports_smpte_level_a_output().ok(),
+            supports_dual_link_sdi: self.supports_dual_link_sdi().ok(),
+            supports_quad_link_sdi: self.supports_quad_link_sdi().ok(),
+            supports_idle_output: self.supports_idle_output().ok(),
+            has_ltc_timecode_input: self.has_ltc_timecode_input().ok(),
+            supports_duplex_mode_configuration: self.supports_duplex_mode_configuration().ok(),
+            supports_hdr_metadata: self.supports_hdr_metadata().ok(),
+            maximum_audio_channels: self.maximum_audio_channels().ok(),
+            maximum_analog_audio_input_channels: self.maximum_analog_audio_input_channels().ok(),
+            maximum_analog_audio_output_channels: self.maximum_analog_audio_output_channels().ok(),
+            number_of_sub_devices: self.number_of_sub_devices().ok(),
+            sub_device_index: self.sub_device_index().ok(),
+            persistent_id: self.persistent_id().ok(),
+            device_group_id: self.device_group_id().ok(),
+            topological_id: self.topological_id().ok(),
+            video_output_connections: self.video_output_connections().ok(),
+            video_input_connections: self.video_input_connections().ok(),
+            audio_output_connections: self.audio_output_connections().ok(),
+            audio_input_connections: self.audio_input_connections().ok(),
+            video_io_support: self.video_io_support().ok(),
+            deck_control_connections: self.deck_control_connections().ok(),
+            device_interface: self.device_interface().ok(),
+            audio_input_rca_channel_count: self.audio_input_rca_channel_count().ok(),
+            audio_input_xlr_channel_count: self.audio_input_xlr_channel_count().ok(),
+            audio_output_rca_channel_count: self.audio_output_rca_channel_count().ok(),
+            audio_output_xlr_channel_count: self.audio_output_xlr_channel_count().ok(),
+            paired_device_persistent_id: self.paired_device_persistent_id().ok(),
+            video_input_gain_minimum: self.video_input_gain_minimum().ok(),
+            video_input_gain_maximum: self.video_input_gain_maximum().ok(),
+            video_output_gain_minimum: self.video_output_gain_minimum().ok(),
+            video_output_gain_maximum: self.video_output_gain_maximum().ok(),
+            microphone_input_gain_minimum: self.microphone_input_gain_minimum().ok(),
+            microphone_input_gain_maximum: self.microphone_input_gain_maximum().ok(),
+            serial_port_device_name: self.serial_port_device_name().ok(),
+            vendor_name: self.vendor_name().ok(),
+            display_name: self.display_name().ok(),
+            model_name: self.model_name().ok(),
+            device_handle: self.device_handle().ok(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn device_interface_round_trips() {
+        let known = [
+            DecklinkDeviceInterface::Pci,
+            DecklinkDeviceInterface::Usb,
+            DecklinkDeviceInterface::Thunderbolt,
+        ];
+        for interface in known {
+            let value = u32::from(interface);
+            assert_eq!(DecklinkDeviceInterface::from(value), interface);
+        }
+
+        let unknown = u32::from_be_bytes(*b"zzzz");
+        assert_eq!(
+            DecklinkDeviceInterface::from(unknown),
+            DecklinkDeviceInterface::Other(unknown)
+        );
+        assert_eq!(u32::from(DecklinkDeviceInterface::Other(unknown)), unknown);
+    }
 }
@@ -0,0 +1,195 @@
+use crate::device::DecklinkDisplayModeSupport;
+use crate::display_mode::DecklinkDisplayModeId;
+use crate::frame::DecklinkPixelFormat;
+use crate::SdkError;
+use strum::IntoEnumIterator;
+
+/// The support for a single combination of display mode, pixel format and flags
+#[derive(PartialEq, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CapabilityEntry<T> {
+    pub mode: DecklinkDisplayModeId,
+    pub pixel_format: DecklinkPixelFormat,
+    pub flags: T,
+    pub support: DecklinkDisplayModeSupport,
+    /// The display mode the hardware will use, which differs from `mode` when supported with conversion
+    pub actual_mode: Option<DecklinkDisplayModeId>,
+}
+
+/// The result of probing every display mode against every pixel format, for a number of flag combinations
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CapabilityMatrix<T> {
+    pub entries: Vec<CapabilityEntry<T>>,
+}
+
+impl<T: Copy> CapabilityMatrix<T> {
+    /// Build a matrix by calling `support_check` for every mode against every pixel format, for each of `flag_sets`.
+    /// `support_check` returns the support and the mode the hardware will use.
+    /// Combinations which fail to be checked are recorded as `NotSupported`.
+    pub fn probe<F>(modes: &[DecklinkDisplayModeId], flag_sets: &[T], support_check: F) -> Self
+    where
+        F: Fn(
+            DecklinkDisplayModeId,
+            DecklinkPixelFormat,
+            T,
+        )
+            -> Result<(DecklinkDisplayModeSupport, Option<DecklinkDisplayModeId>), SdkError>,
+    {
+        let mut entries = Vec::new();
+        for mode in modes {
+            for pixel_format in DecklinkPixelFormat::iter() {
+                for flags in flag_sets {
+                    let (support, actual_mode) = support_check(*mode, pixel_format, *flags)
+                        .unwrap_or((DecklinkDisplayModeSupport::NotSupported, None));
+                    entries.push(CapabilityEntry {
+                        mode: *mode,
+                        pixel_format,
+                        flags: *flags,
+                        support,
+                        actual_mode,
+                    });
+                }
+            }
+        }
+
+        CapabilityMatrix { entries }
+    }
+}
+
+impl<T: PartialEq + Copy> CapabilityMatrix<T> {
+    /// Look up the support for a combination.
+    /// Combinations which were not probed are reported as `NotSupported`.
+    pub fn get(
+        &self,
+        mode: DecklinkDisplayModeId,
+        pixel_format: DecklinkPixelFormat,
+        flags: T,
+    ) -> DecklinkDisplayModeSupport {
+        self.entries
+            .iter()
+            .find(|e| e.mode == mode && e.pixel_format == pixel_format && e.flags == flags)
+            .map(|e| e.support)
+            .unwrap_or(DecklinkDisplayModeSupport::NotSupported)
+    }
+
+    /// The combinations which are supported, either natively or with conversion
+    pub fn supported(&self) -> impl Iterator<Item = &CapabilityEntry<T>> {
+        self.entries
+            .iter()
+            .filter(|e| e.support != DecklinkDisplayModeSupport::NotSupported)
+    }
+
+    /// The pixel formats that a display mode is supported in with the given flags
+    pub fn pixel_formats(&self, mode: DecklinkDisplayModeId, flags: T) -> Vec<DecklinkPixelFormat> {
+        self.supported()
+            .filter(|e| e.mode == mode && e.flags == flags)
+            .map(|e| e.pixel_format)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use DecklinkDisplayModeId::*;
+    use DecklinkDisplayModeSupport::*;
+    use DecklinkPixelFormat::*;
+
+    const DUAL_STREAM: u32 = 1;
+
+    /// A device which supports 1080i50 in 8bit and 10bit yuv, converts 1080p25 to 1080i50,
+    /// and only supports the dual stream flag in 8bit yuv
+    fn matrix() -> CapabilityMatrix<u32> {
+        CapabilityMatrix::probe(
+            &[HD1080i50, HD1080p25, HD720p50],
+            &[0, DUAL_STREAM],
+            |mode, pixel_format, flags| match (mode, pixel_format, flags) {
+                (HD720p50, _, _) => Err(SdkError::FAIL),
+                (HD1080i50, Format8BitYUV, _) => Ok((Supported, None)),
+                (HD1080i50, Format10BitYUV, 0) => Ok((Supported, None)),
+                (HD1080p25, Format8BitYUV, 0) => Ok((SupportedWithConversion, Some(HD1080i50))),
+                _ => Ok((NotSupported, None)),
+            },
+        )
+    }
+
+    #[test]
+    fn probes_every_combination() {
+        let matrix = matrix();
+        let pixel_formats = DecklinkPixelFormat::iter().count();
+        assert_eq!(matrix.entries.len(), 3 * 2 * pixel_formats);
+    }
+
+    #[test]
+    fn get_looks_up_support() {
+        let matrix = matrix();
+
+        assert_eq!(matrix.get(HD1080i50, Format8BitYUV, 0), Supported);
+        assert_eq!(matrix.get(HD1080i50, Format8BitYUV, DUAL_STREAM), Supported);
+        assert_eq!(matrix.get(HD1080i50, Format10BitYUV, 0), Supported);
+        assert_eq!(
+            matrix.get(HD1080i50, Format10BitYUV, DUAL_STREAM),
+            NotSupported
+        );
+        assert_eq!(
+            matrix.get(HD1080p25, Format8BitYUV, 0),
+            SupportedWithConversion
+        );
+        // Failed checks are not supported
+        assert_eq!(matrix.get(HD720p50, Format8BitYUV, 0), NotSupported);
+        // As are combinations which were never probed
+        assert_eq!(matrix.get(HD1080p50, Format8BitYUV, 0), NotSupported);
+        assert_eq!(matrix.get(HD1080i50, Format8BitYUV, 2), NotSupported);
+    }
+
+    #[test]
+    fn supported_filters_entries() {
+        let matrix = matrix();
+        let supported: Vec<_> = matrix
+            .supported()
+            .map(|e| (e.mode, e.pixel_format, e.flags, e.support, e.actual_mode))
+            .collect();
+
+        assert_eq!(
+            supported,
+            [
+                (HD1080i50, Format8BitYUV, 0, Supported, None),
+                (HD1080i50, Format8BitYUV, DUAL_STREAM, Supported, None),
+                (HD1080i50, Format10BitYUV, 0, Supported, None),
+                (
+                    HD1080p25,
+                    Format8BitYUV,
+                    0,
+                    SupportedWithConversion,
+                    Some(HD1080i50)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn pixel_formats_of_mode() {
+        let matrix = matrix();
+
+        assert_eq!(
+            matrix.pixel_formats(HD1080i50, 0),
+            [Format8BitYUV, Format10BitYUV]
+        );
+        assert_eq!(
+            matrix.pixel_formats(HD1080i50, DUAL_STREAM),
+            [Format8BitYUV]
+        );
+        assert_eq!(matrix.pixel_formats(HD1080p25, 0), [Format8BitYUV]);
+        assert!(matrix.pixel_formats(HD1080p25, DUAL_STREAM).is_empty());
+        assert!(matrix.pixel_formats(HD720p50, 0).is_empty());
+    }
+
+    #[test]
+    fn probe_without_flag_sets_is_empty() {
+        let matrix =
+            CapabilityMatrix::<u32>::probe(&[HD1080i50], &[], |_, _, _| Ok((Supported, None)));
+        assert!(matrix.entries.is_empty());
+        assert!(matrix.pixel_formats(HD1080i50, 0).is_empty());
+    }
+}
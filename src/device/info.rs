@@ -0,0 +1,84 @@
+use crate::device::attributes::AttributesSnapshot;
+use crate::device::input::DecklinkVideoInputFlags;
+use crate::device::output::DecklinkVideoOutputFlags;
+use crate::device::status::StatusSnapshot;
+use crate::device::{DecklinkDevice, DecklinkDeviceDisplayModes, DecklinkDisplayModeSupport};
+use crate::display_mode::{DecklinkDisplayModeId, DecklinkFieldDominance};
+use crate::frame::DecklinkPixelFormat;
+use strum::IntoEnumIterator;
+
+/// A display mode, along with the pixel formats it is natively supported in
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DisplayModeInfo {
+    pub mode: DecklinkDisplayModeId,
+    pub name: Option<String>,
+    pub width: usize,
+    pub height: usize,
+    /// The frame duration and timescale
+    pub framerate: Option<(i64, i64)>,
+    pub field_dominance: DecklinkFieldDominance,
+    pub pixel_formats: Vec<DecklinkPixelFormat>,
+}
+
+/// A copy of everything known about a device, gathered in one go.
+/// With the `serde` feature enabled, this can be serialized for inventory or diagnostics.
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeviceInfo {
+    pub model_name: Option<String>,
+    pub display_name: Option<String>,
+    pub attributes: Option<AttributesSnapshot>,
+    /// The output display modes, or `None` if the device has no output
+    pub output_modes: Option<Vec<DisplayModeInfo>>,
+    /// The input display modes, or `None` if the device has no input
+    pub input_modes: Option<Vec<DisplayModeInfo>>,
+    pub status: Option<StatusSnapshot>,
+}
+
+fn collect_modes<T: Copy>(
+    dev: &dyn DecklinkDeviceDisplayModes<T>,
+    flags: T,
+) -> Option<Vec<DisplayModeInfo>> {
+    let modes = dev.display_modes().ok()?;
+    Some(
+        modes
+            .iter()
+            .map(|mode| DisplayModeInfo {
+                mode: mode.mode(),
+                name: mode.name(),
+                width: mode.width(),
+                height: mode.height(),
+                framerate: mode.framerate(),
+                field_dominance: mode.field_dominance(),
+                pixel_formats: DecklinkPixelFormat::iter()
+                    .filter(|format| {
+                        matches!(
+                            dev.does_support_video_mode(mode.mode(), *format, flags),
+                            Ok((DecklinkDisplayModeSupport::Supported, _))
+                        )
+                    })
+                    .collect(),
+            })
+            .collect(),
+    )
+}
+
+impl DeviceInfo {
+    /// Gather the information about a device.
+    /// Anything that cannot be read is left as `None`.
+    pub fn capture(device: &DecklinkDevice) -> DeviceInfo {
+        DeviceInfo {
+            model_name: device.model_name(),
+            display_name: device.display_name(),
+            attributes: device.get_attributes().ok().map(|a| a.snapshot()),
+            output_modes: device
+                .output()
+                .and_then(|output| collect_modes(&output, DecklinkVideoOutputFlags::empty())),
+            input_modes: device
+                .input()
+                .and_then(|input| collect_modes(&input, DecklinkVideoInputFlags::empty())),
+            status: device.get_status().ok().map(|s| s.snapshot()),
+        }
+    }
+}
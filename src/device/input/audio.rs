@@ -0,0 +1,71 @@
+use crate::{sdk, SdkError};
+use std::ptr::null_mut;
+
+/// A packet of audio samples that has been received from a decklink device.
+pub struct DecklinkAudioInputPacket {
+    packet: *mut sdk::cdecklink_audio_input_packet_t,
+    sample_frame_bytes: usize,
+}
+
+// The sdk object is reference counted and safe to use and release from any thread
+unsafe impl Send for DecklinkAudioInputPacket {}
+
+impl Drop for DecklinkAudioInputPacket {
+    fn drop(&mut self) {
+        if !self.packet.is_null() {
+            unsafe { sdk::cdecklink_audio_input_packet_release(self.packet) };
+            self.packet = null_mut();
+        }
+    }
+}
+
+impl Clone for DecklinkAudioInputPacket {
+    fn clone(&self) -> Self {
+        unsafe { Self::from(self.packet, self.sample_frame_bytes) }
+    }
+}
+
+impl DecklinkAudioInputPacket {
+    /// Wrap a borrowed raw pointer, taking a new reference to it
+    pub(crate) unsafe fn from(
+        ptr: *mut sdk::cdecklink_audio_input_packet_t,
+        sample_frame_bytes: usize,
+    ) -> Self {
+        sdk::cdecklink_audio_input_packet_add_ref(ptr);
+        Self {
+            packet: ptr,
+            sample_frame_bytes,
+        }
+    }
+
+    /// Get the number of sample frames in the packet
+    pub fn sample_frame_count(&self) -> usize {
+        let count =
+            unsafe { sdk::cdecklink_audio_input_packet_get_sample_frame_count(self.packet) };
+        count as usize
+    }
+
+    /// Get the interleaved sample data of the packet
+    pub fn bytes(&self) -> Result<&[u8], SdkError> {
+        let mut bytes = null_mut();
+        let result =
+            unsafe { sdk::cdecklink_audio_input_packet_get_bytes(self.packet, &mut bytes) };
+        SdkError::result::<()>(result)?;
+
+        if bytes.is_null() {
+            Err(SdkError::FAIL)
+        } else {
+            let byte_count = self.sample_frame_count() * self.sample_frame_bytes;
+            Ok(unsafe { std::slice::from_raw_parts(bytes as *const u8, byte_count) })
+        }
+    }
+
+    /// Get the time of the packet in the requested timescale
+    pub fn packet_time(&self, timescale: i64) -> Result<i64, SdkError> {
+        let mut time = 0;
+        let result = unsafe {
+            sdk::cdecklink_audio_input_packet_get_packet_time(self.packet, &mut time, timescale)
+        };
+        SdkError::result_or(result, time)
+    }
+}
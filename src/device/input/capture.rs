@@ -0,0 +1,439 @@
+use crate::device::input::audio::DecklinkAudioInputPacket;
+use crate::device::input::enums::{
+    DecklinkDetectedVideoInputFormatFlags, DecklinkVideoInputFormatChangedEvents,
+};
+use crate::device::input::video_callback::DeckLinkVideoInputCallback;
+use crate::display_mode::DecklinkDisplayMode;
+use crate::frame::DecklinkVideoInputFrame;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::task::Waker;
+use std::time::{Duration, Instant};
+
+/// The video and audio delivered by a single frame arrived callback
+pub struct CapturedFrame {
+    /// The number of frames delivered by the device before this one, so that gaps show where frames were dropped
+    pub sequence: u64,
+    pub video: Option<DecklinkVideoInputFrame>,
+    pub audio: Option<DecklinkAudioInputPacket>,
+}
+
+/// What to do when a frame arrives and the capture queue is full
+#[derive(PartialEq, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CaptureOverflowPolicy {
+    /// Discard the oldest queued frame to make room for the new one
+    DropOldest,
+    /// Discard the new frame
+    DropNewest,
+    /// Block the decklink callback thread until there is room, for up to `timeout` before discarding the new frame.
+    /// While blocked, the driver will discard frames itself, and these are not counted.
+    /// Stopping the streams waits for the callback to return, so can take up to `timeout`.
+    Block { timeout: Duration },
+}
+
+struct QueueState {
+    frames: VecDeque<CapturedFrame>,
+    /// The callback has been removed from the device, so no more frames will arrive
+    closed: bool,
+    /// The receiving end has been dropped, so nobody will take any more frames
+    abandoned: bool,
+    /// The task waiting on a `CaptureStream`, if any
+    waker: Option<Waker>,
+}
+
+struct CaptureShared {
+    capacity: usize,
+    policy: CaptureOverflowPolicy,
+
+    state: Mutex<QueueState>,
+    /// Signalled when a frame is pushed, or the queue is closed
+    frame_ready: Condvar,
+    /// Signalled when a frame is popped, or the queue is abandoned
+    space_ready: Condvar,
+
+    received: AtomicU64,
+    dropped: AtomicU64,
+}
+
+impl CaptureShared {
+    fn lock(&self) -> MutexGuard<'_, QueueState> {
+        match self.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    fn push(
+        &self,
+        video: Option<DecklinkVideoInputFrame>,
+        audio: Option<DecklinkAudioInputPacket>,
+    ) {
+        let frame = CapturedFrame {
+            sequence: self.received.fetch_add(1, Ordering::Relaxed),
+            video,
+            audio,
+        };
+
+        let mut state = self.lock();
+        if state.abandoned {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+
+        if state.frames.len() >= self.capacity {
+            match self.policy {
+                CaptureOverflowPolicy::DropOldest => {
+                    state.frames.pop_front();
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+                CaptureOverflowPolicy::DropNewest => {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                    return;
+                }
+                CaptureOverflowPolicy::Block { timeout } => {
+                    let deadline = Instant::now() + timeout;
+                    while state.frames.len() >= self.capacity && !state.abandoned {
+                        let now = Instant::now();
+                        if now >= deadline {
+                            break;
+                        }
+                        state = match self.space_ready.wait_timeout(state, deadline - now) {
+                            Ok((state, _)) => state,
+                            Err(poisoned) => poisoned.into_inner().0,
+                        };
+                    }
+                    if state.abandoned || state.frames.len() >= self.capacity {
+                        self.dropped.fetch_add(1, Ordering::Relaxed);
+                        return;
+                    }
+                }
+            }
+        }
+
+        state.frames.push_back(frame);
+        let waker = state.waker.take();
+        drop(state);
+        self.frame_ready.notify_one();
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    fn close(&self) {
+        let mut state = self.lock();
+        state.closed = true;
+        let waker = state.waker.take();
+        drop(state);
+        self.frame_ready.notify_all();
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+/// The callback installed on the device by `DecklinkInputDevice::frames`
+struct CaptureCallback {
+    shared: Arc<CaptureShared>,
+}
+
+impl Drop for CaptureCallback {
+    fn drop(&mut self) {
+        // The callback is dropped once it has been replaced or the device has been released
+        self.shared.close();
+    }
+}
+
+impl DeckLinkVideoInputCallback for CaptureCallback {
+    fn video_input_format_changed(
+        &self,
+        _events: DecklinkVideoInputFormatChangedEvents,
+        _display_mode: Option<DecklinkDisplayMode>,
+        _detected_signal_flags: DecklinkDetectedVideoInputFormatFlags,
+    ) -> bool {
+        true
+    }
+
+    fn video_input_frame_arrived(
+        &self,
+        video_frame: Option<DecklinkVideoInputFrame>,
+        audio_packet: Option<DecklinkAudioInputPacket>,
+    ) -> bool {
+        self.shared.push(video_frame, audio_packet);
+        true
+    }
+}
+
+/// A bounded queue of the frames captured by an input, created by `DecklinkInputDevice::frames`.
+/// Iterating blocks until the next frame arrives, and ends once the callback is replaced or the device is dropped.
+pub struct CaptureFrames {
+    shared: Arc<CaptureShared>,
+}
+
+impl Drop for CaptureFrames {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock();
+        state.abandoned = true;
+        state.frames.clear();
+        drop(state);
+        // Release the callback thread if it is blocked waiting for room
+        self.shared.space_ready.notify_all();
+    }
+}
+
+impl CaptureFrames {
+    pub(crate) fn new(
+        capacity: usize,
+        policy: CaptureOverflowPolicy,
+    ) -> (CaptureFrames, Arc<dyn DeckLinkVideoInputCallback>) {
+        let shared = Arc::new(CaptureShared {
+            capacity: capacity.max(1),
+            policy,
+            state: Mutex::new(QueueState {
+                frames: VecDeque::with_capacity(capacity),
+                closed: false,
+                abandoned: false,
+                waker: None,
+            }),
+            frame_ready: Condvar::new(),
+            space_ready: Condvar::new(),
+            received: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+        });
+        let callback = Arc::new(CaptureCallback {
+            shared: shared.clone(),
+        });
+        (CaptureFrames { shared }, callback)
+    }
+
+    fn pop(&self, mut state: MutexGuard<'_, QueueState>) -> Option<CapturedFrame> {
+        let frame = state.frames.pop_front();
+        drop(state);
+        if frame.is_some() {
+            self.shared.space_ready.notify_one();
+        }
+        frame
+    }
+
+    /// Wait for the next frame.
+    /// Returns `None` once the queue is empty and no more frames will arrive.
+    pub fn recv(&self) -> Option<CapturedFrame> {
+        let mut state = self.shared.lock();
+        while state.frames.is_empty() && !state.closed {
+            state = match self.shared.frame_ready.wait(state) {
+                Ok(state) => state,
+                Err(poisoned) => poisoned.into_inner(),
+            };
+        }
+        self.pop(state)
+    }
+
+    /// Wait up to `timeout` for the next frame
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<CapturedFrame> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.shared.lock();
+        while state.frames.is_empty() && !state.closed {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            state = match self.shared.frame_ready.wait_timeout(state, deadline - now) {
+                Ok((state, _)) => state,
+                Err(poisoned) => poisoned.into_inner().0,
+            };
+        }
+        self.pop(state)
+    }
+
+    /// Take the next frame if one is queued, without waiting
+    pub fn try_recv(&self) -> Option<CapturedFrame> {
+        let state = self.shared.lock();
+        self.pop(state)
+    }
+
+    /// The number of frames currently queued
+    pub fn len(&self) -> usize {
+        self.shared.lock().frames.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether the callback has been removed, so that no more frames will arrive
+    pub fn is_closed(&self) -> bool {
+        self.shared.lock().closed
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity
+    }
+    pub fn overflow_policy(&self) -> CaptureOverflowPolicy {
+        self.shared.policy
+    }
+
+    /// The number of frames delivered by the device, including any that were discarded
+    pub fn received_count(&self) -> u64 {
+        self.shared.received.load(Ordering::Relaxed)
+    }
+    /// The number of frames discarded because the queue was full
+    pub fn dropped_count(&self) -> u64 {
+        self.shared.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Receive the frames as a `futures::Stream`, instead of blocking
+    #[cfg(feature = "async")]
+    pub fn into_stream(self) -> CaptureStream {
+        CaptureStream { frames: self }
+    }
+}
+
+impl Iterator for CaptureFrames {
+    type Item = CapturedFrame;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.recv()
+    }
+}
+
+/// The frames captured by an input as a `futures::Stream`, created by `CaptureFrames::into_stream`.
+/// The stream ends once the callback is replaced or the device is dropped.
+#[cfg(feature = "async")]
+pub struct CaptureStream {
+    frames: CaptureFrames,
+}
+
+#[cfg(feature = "async")]
+impl CaptureStream {
+    /// The queue that the stream is reading from, for the capacity and counters
+    pub fn frames(&self) -> &CaptureFrames {
+        &self.frames
+    }
+}
+
+#[cfg(feature = "async")]
+impl futures::Stream for CaptureStream {
+    type Item = CapturedFrame;
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        let mut state = self.frames.shared.lock();
+        if state.frames.is_empty() {
+            if state.closed {
+                return std::task::Poll::Ready(None);
+            }
+            state.waker = Some(cx.waker().clone());
+            return std::task::Poll::Pending;
+        }
+        std::task::Poll::Ready(self.frames.pop(state))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    fn arrive(callback: &Arc<dyn DeckLinkVideoInputCallback>, count: usize) {
+        for _ in 0..count {
+            callback.video_input_frame_arrived(None, None);
+        }
+    }
+
+    fn sequences(frames: &CaptureFrames) -> Vec<u64> {
+        std::iter::from_fn(|| frames.try_recv())
+            .map(|frame| frame.sequence)
+            .collect()
+    }
+
+    #[test]
+    fn drop_oldest() {
+        let (frames, callback) = CaptureFrames::new(2, CaptureOverflowPolicy::DropOldest);
+        arrive(&callback, 5);
+
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames.received_count(), 5);
+        assert_eq!(frames.dropped_count(), 3);
+        assert_eq!(sequences(&frames), vec![3, 4]);
+    }
+
+    #[test]
+    fn drop_newest() {
+        let (frames, callback) = CaptureFrames::new(2, CaptureOverflowPolicy::DropNewest);
+        arrive(&callback, 5);
+
+        assert_eq!(frames.received_count(), 5);
+        assert_eq!(frames.dropped_count(), 3);
+        assert_eq!(sequences(&frames), vec![0, 1]);
+    }
+
+    #[test]
+    fn block_waits_for_space() {
+        let policy = CaptureOverflowPolicy::Block {
+            timeout: Duration::from_secs(10),
+        };
+        let (frames, callback) = CaptureFrames::new(1, policy);
+        arrive(&callback, 1);
+
+        let shared = frames.shared.clone();
+        let producer = thread::spawn(move || shared.push(None, None));
+        // The second frame can only be queued once the first has been taken
+        while frames.received_count() < 2 {
+            thread::yield_now();
+        }
+        assert_eq!(frames.recv().map(|f| f.sequence), Some(0));
+        producer.join().unwrap();
+
+        assert_eq!(frames.recv().map(|f| f.sequence), Some(1));
+        assert_eq!(frames.dropped_count(), 0);
+
+        // Once the callback has gone, no more frames will arrive
+        drop(callback);
+        assert!(frames.is_closed());
+        assert!(frames.recv().is_none());
+    }
+
+    #[test]
+    fn block_gives_up_after_timeout() {
+        let policy = CaptureOverflowPolicy::Block {
+            timeout: Duration::from_millis(10),
+        };
+        let (frames, callback) = CaptureFrames::new(1, policy);
+        arrive(&callback, 3);
+
+        assert_eq!(frames.received_count(), 3);
+        assert_eq!(frames.dropped_count(), 2);
+        assert_eq!(sequences(&frames), vec![0]);
+    }
+
+    #[test]
+    fn block_is_released_when_abandoned() {
+        let policy = CaptureOverflowPolicy::Block {
+            timeout: Duration::from_secs(10),
+        };
+        let (frames, callback) = CaptureFrames::new(1, policy);
+        arrive(&callback, 1);
+
+        let shared = frames.shared.clone();
+        let producer = {
+            let shared = shared.clone();
+            thread::spawn(move || shared.push(None, None))
+        };
+        while shared.received.load(Ordering::Relaxed) < 2 {
+            thread::yield_now();
+        }
+        drop(frames);
+        producer.join().unwrap();
+
+        assert_eq!(shared.dropped.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn recv_timeout_when_empty() {
+        let (frames, _callback) = CaptureFrames::new(1, CaptureOverflowPolicy::DropOldest);
+        assert!(frames.recv_timeout(Duration::from_millis(1)).is_none());
+        assert!(!frames.is_closed());
+    }
+}
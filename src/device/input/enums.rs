@@ -0,0 +1,30 @@
+use crate::sdk;
+
+bitflags! {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct DecklinkVideoInputFlags: u32 {
+        const ENABLE_FORMAT_DETECTION = sdk::_DecklinkVideoInputFlags_decklinkVideoInputEnableFormatDetection;
+        const DUAL_STREAM_3D = sdk::_DecklinkVideoInputFlags_decklinkVideoInputDualStream3D;
+    }
+}
+
+bitflags! {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct DecklinkVideoInputFormatChangedEvents: u32 {
+        const DISPLAY_MODE_CHANGED = sdk::_DecklinkVideoInputFormatChangedEvents_decklinkVideoInputDisplayModeChanged;
+        const FIELD_DOMINANCE_CHANGED = sdk::_DecklinkVideoInputFormatChangedEvents_decklinkVideoInputFieldDominanceChanged;
+        const COLORSPACE_CHANGED = sdk::_DecklinkVideoInputFormatChangedEvents_decklinkVideoInputColorspaceChanged;
+    }
+}
+
+bitflags! {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct DecklinkDetectedVideoInputFormatFlags: u32 {
+        const YCBCR_422 = sdk::_DecklinkDetectedVideoInputFormatFlags_decklinkDetectedVideoInputYCbCr422;
+        const RGB_444 = sdk::_DecklinkDetectedVideoInputFormatFlags_decklinkDetectedVideoInputRGB444;
+        const DUAL_STREAM_3D = sdk::_DecklinkDetectedVideoInputFormatFlags_decklinkDetectedVideoInputDualStream3D;
+    }
+}
@@ -0,0 +1,342 @@
+mod audio;
+mod capture;
+mod enums;
+mod video_callback;
+
+use crate::device::input::video_callback::{free_callback_wrapper, register_callback};
+use crate::device::output::{DecklinkAudioSampleRate, DecklinkAudioSampleType};
+use crate::device::teardown::{teardown_input, InputEnabled, InputOps};
+use crate::device::{
+    DecklinkDeviceDisplayModes, DecklinkDisplayModeSupport, DecklinkHardwareReferenceClock,
+};
+use crate::display_mode::{
+    iterate_display_modes, wrap_display_mode, DecklinkDisplayMode, DecklinkDisplayModeId,
+};
+use crate::frame::DecklinkPixelFormat;
+use crate::{sdk, SdkError};
+use num_traits::FromPrimitive;
+use std::cell::Cell;
+use std::ptr::null_mut;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+pub use crate::device::input::audio::DecklinkAudioInputPacket;
+#[cfg(feature = "async")]
+pub use crate::device::input::capture::CaptureStream;
+pub use crate::device::input::capture::{CaptureFrames, CaptureOverflowPolicy, CapturedFrame};
+pub use crate::device::input::enums::*;
+pub use crate::device::input::video_callback::DeckLinkVideoInputCallback;
+
+use self::video_callback::CallbackWrapper;
+
+pub struct DecklinkInputDevice {
+    dev: *mut crate::sdk::cdecklink_input_t,
+    /// The device this input belongs to, which is kept alive for as long as the input
+    parent: *mut crate::sdk::cdecklink_device_t,
+    /// Only registered with the sdk once a callback is set, so that probing an input does not disturb another capture
+    callback_wrapper: Cell<*mut CallbackWrapper>,
+    audio_sample_frame_bytes: Cell<usize>,
+
+    /// What this handle has enabled, which is all that gets disabled when it is dropped
+    video_enabled: Cell<bool>,
+    audio_enabled: Cell<bool>,
+    streams_started: Cell<bool>,
+}
+
+impl Drop for DecklinkInputDevice {
+    fn drop(&mut self) {
+        if !self.dev.is_null() {
+            let callback_wrapper = self.callback_wrapper.replace(null_mut());
+            let enabled = InputEnabled {
+                video: self.video_enabled.get(),
+                audio: self.audio_enabled.get(),
+                streams: self.streams_started.get(),
+            };
+            teardown_input(
+                &SdkInput(self.dev),
+                enabled,
+                (!callback_wrapper.is_null()).then_some(callback_wrapper),
+            );
+
+            unsafe {
+                sdk::cdecklink_input_release(self.dev);
+                sdk::cdecklink_device_release(self.parent);
+            }
+            self.dev = null_mut();
+            self.parent = null_mut();
+        }
+    }
+}
+
+/// The sdk calls made when tearing down an input
+struct SdkInput(*mut crate::sdk::cdecklink_input_t);
+impl InputOps for SdkInput {
+    type Callback = *mut CallbackWrapper;
+
+    fn stop_streams(&self) {
+        unsafe { sdk::cdecklink_input_stop_streams(self.0) };
+    }
+    fn disable_video(&self) {
+        unsafe { sdk::cdecklink_input_disable_video_input(self.0) };
+    }
+    fn disable_audio(&self) {
+        unsafe { sdk::cdecklink_input_disable_audio_input(self.0) };
+    }
+    fn unregister_callback(&self, callback: *mut CallbackWrapper) {
+        // Stopping the streams ensures there are no callbacks in progress
+        unsafe { sdk::cdecklink_input_set_callback(self.0, null_mut(), None, None) };
+        free_callback_wrapper(callback);
+    }
+}
+
+impl DecklinkDeviceDisplayModes<enums::DecklinkVideoInputFlags> for DecklinkInputDevice {
+    fn does_support_video_mode(
+        &self,
+        mode: DecklinkDisplayModeId,
+        pixel_format: DecklinkPixelFormat,
+        flags: enums::DecklinkVideoInputFlags,
+    ) -> Result<(DecklinkDisplayModeSupport, Option<DecklinkDisplayMode>), SdkError> {
+        let mut supported = sdk::_DecklinkDisplayModeSupport_decklinkDisplayModeNotSupported;
+        let mut display_mode = null_mut();
+        let result = unsafe {
+            sdk::cdecklink_input_does_support_video_mode(
+                self.dev,
+                u32::from(mode),
+                u32::from(pixel_format),
+                flags.bits(),
+                &mut supported,
+                &mut display_mode,
+            )
+        };
+        SdkError::result_or_else(result, move || {
+            let supported2 = DecklinkDisplayModeSupport::from_u32(supported)
+                .unwrap_or(DecklinkDisplayModeSupport::NotSupported);
+            if display_mode.is_null() || supported2 == DecklinkDisplayModeSupport::NotSupported {
+                (DecklinkDisplayModeSupport::NotSupported, None)
+            } else {
+                unsafe { (supported2, Some(wrap_display_mode(display_mode))) }
+            }
+        })
+    }
+
+    fn capability_flag_sets(&self) -> Vec<enums::DecklinkVideoInputFlags> {
+        vec![
+            enums::DecklinkVideoInputFlags::empty(),
+            enums::DecklinkVideoInputFlags::DUAL_STREAM_3D,
+        ]
+    }
+
+    fn display_modes(&self) -> Result<Vec<DecklinkDisplayMode>, SdkError> {
+        unsafe {
+            let mut it = null_mut();
+            let ok = sdk::cdecklink_input_get_display_mode_iterator(self.dev, &mut it);
+            if SdkError::is_ok(ok) {
+                let v = iterate_display_modes(it);
+                sdk::cdecklink_display_mode_iterator_release(it);
+                v
+            } else {
+                Err(SdkError::from(ok))
+            }
+        }
+    }
+}
+
+impl DecklinkInputDevice {
+    /// Wrap an input that was queried from `parent`. This takes ownership of `ptr`, and a new reference to `parent`
+    pub(crate) unsafe fn from(
+        parent: *mut crate::sdk::cdecklink_device_t,
+        ptr: *mut crate::sdk::cdecklink_input_t,
+    ) -> Result<DecklinkInputDevice, SdkError> {
+        sdk::cdecklink_device_add_ref(parent);
+        Ok(DecklinkInputDevice {
+            dev: ptr,
+            parent,
+            callback_wrapper: Cell::new(null_mut()),
+            audio_sample_frame_bytes: Cell::new(0),
+            video_enabled: Cell::new(false),
+            audio_enabled: Cell::new(false),
+            streams_started: Cell::new(false),
+        })
+    }
+
+    /// Get the callback wrapper, registering it with the sdk if this is the first callback to be set
+    fn callback_wrapper(&self) -> Result<&CallbackWrapper, SdkError> {
+        let mut wrapper = self.callback_wrapper.get();
+        if wrapper.is_null() {
+            wrapper = register_callback(self.dev)?;
+            unsafe {
+                (*wrapper)
+                    .audio_sample_frame_bytes
+                    .store(self.audio_sample_frame_bytes.get(), Ordering::Relaxed)
+            };
+            self.callback_wrapper.set(wrapper);
+        }
+        Ok(unsafe { &*wrapper })
+    }
+
+    /// Set the callback to receive the captured frames.
+    /// The first callback set replaces any callback registered by another handle to the same input.
+    pub fn set_callback(
+        &self,
+        handler: Option<Arc<dyn DeckLinkVideoInputCallback>>,
+    ) -> Result<(), SdkError> {
+        if handler.is_none() && self.callback_wrapper.get().is_null() {
+            // Nothing has been registered, so there is nothing to clear
+            return Ok(());
+        }
+
+        let wrapper = self.callback_wrapper()?;
+        let mut current = match wrapper.handler.write() {
+            Ok(current) => current,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        *current = handler;
+        Ok(())
+    }
+
+    /// Receive captured frames through a bounded queue, instead of a callback.
+    /// The oldest frame is discarded when the queue is full.
+    /// This fails with `ACCESSDENIED` if a callback is already set, which must be cleared with `set_callback(None)` first.
+    pub fn frames(&self, capacity: usize) -> Result<CaptureFrames, SdkError> {
+        self.frames_with_policy(capacity, CaptureOverflowPolicy::DropOldest)
+    }
+
+    /// Receive captured frames through a bounded queue, with the chosen behaviour for when the queue is full.
+    /// This fails with `ACCESSDENIED` if a callback is already set, which must be cleared with `set_callback(None)` first.
+    pub fn frames_with_policy(
+        &self,
+        capacity: usize,
+        policy: CaptureOverflowPolicy,
+    ) -> Result<CaptureFrames, SdkError> {
+        let wrapper = self.callback_wrapper()?;
+        let mut current = match wrapper.handler.write() {
+            Ok(current) => current,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        if current.is_some() {
+            return Err(SdkError::ACCESSDENIED);
+        }
+
+        let (frames, callback) = CaptureFrames::new(capacity, policy);
+        *current = Some(callback);
+        Ok(frames)
+    }
+
+    /* Video Input */
+
+    pub fn enable_video_input(
+        &self,
+        mode: DecklinkDisplayModeId,
+        pixel_format: DecklinkPixelFormat,
+        flags: enums::DecklinkVideoInputFlags,
+    ) -> Result<(), SdkError> {
+        let result = unsafe {
+            sdk::cdecklink_input_enable_video_input(
+                self.dev,
+                u32::from(mode),
+                u32::from(pixel_format),
+                flags.bits(),
+            )
+        };
+        SdkError::result_or_else(result, || self.video_enabled.set(true))
+    }
+    pub fn disable_video_input(&self) -> Result<(), SdkError> {
+        let result = unsafe { sdk::cdecklink_input_disable_video_input(self.dev) };
+        SdkError::result_or_else(result, || self.video_enabled.set(false))
+    }
+    pub fn available_video_frame_count(&self) -> Result<u32, SdkError> {
+        let mut count = 0;
+        let result =
+            unsafe { sdk::cdecklink_input_get_available_video_frame_count(self.dev, &mut count) };
+        SdkError::result_or(result, count)
+    }
+
+    /* Audio Input */
+
+    pub fn enable_audio_input(
+        &self,
+        sample_rate: DecklinkAudioSampleRate,
+        sample_type: DecklinkAudioSampleType,
+        channels: u32,
+    ) -> Result<(), SdkError> {
+        let result = unsafe {
+            sdk::cdecklink_input_enable_audio_input(
+                self.dev,
+                sample_rate as u32,
+                sample_type as u32,
+                channels,
+            )
+        };
+        SdkError::result_or_else(result, || {
+            self.audio_enabled.set(true);
+
+            let sample_frame_bytes = sample_type as usize / 8 * channels as usize;
+            self.audio_sample_frame_bytes.set(sample_frame_bytes);
+            let wrapper = self.callback_wrapper.get();
+            if !wrapper.is_null() {
+                unsafe {
+                    (*wrapper)
+                        .audio_sample_frame_bytes
+                        .store(sample_frame_bytes, Ordering::Relaxed)
+                };
+            }
+        })
+    }
+    pub fn disable_audio_input(&self) -> Result<(), SdkError> {
+        let result = unsafe { sdk::cdecklink_input_disable_audio_input(self.dev) };
+        SdkError::result_or_else(result, || self.audio_enabled.set(false))
+    }
+    pub fn available_audio_sample_frame_count(&self) -> Result<u32, SdkError> {
+        let mut count = 0;
+        let result = unsafe {
+            sdk::cdecklink_input_get_available_audio_sample_frame_count(self.dev, &mut count)
+        };
+        SdkError::result_or(result, count)
+    }
+
+    /* Streams */
+
+    pub fn start_streams(&self) -> Result<(), SdkError> {
+        let result = unsafe { sdk::cdecklink_input_start_streams(self.dev) };
+        SdkError::result_or_else(result, || self.streams_started.set(true))
+    }
+    pub fn stop_streams(&self) -> Result<(), SdkError> {
+        let result = unsafe { sdk::cdecklink_input_stop_streams(self.dev) };
+        SdkError::result_or_else(result, || self.streams_started.set(false))
+    }
+    pub fn pause_streams(&self) -> Result<(), SdkError> {
+        let result = unsafe { sdk::cdecklink_input_pause_streams(self.dev) };
+        SdkError::result(result)
+    }
+    pub fn flush_streams(&self) -> Result<(), SdkError> {
+        let result = unsafe { sdk::cdecklink_input_flush_streams(self.dev) };
+        SdkError::result(result)
+    }
+
+    /// Get the current time of the hardware reference clock, in the requested timescale.
+    pub fn hardware_reference_clock(
+        &self,
+        timescale: i64,
+    ) -> Result<DecklinkHardwareReferenceClock, SdkError> {
+        let mut hardware_time = 0;
+        let mut time_in_frame = 0;
+        let mut ticks_per_frame = 0;
+        let result = unsafe {
+            sdk::cdecklink_input_get_hardware_reference_clock(
+                self.dev,
+                timescale,
+                &mut hardware_time,
+                &mut time_in_frame,
+                &mut ticks_per_frame,
+            )
+        };
+        SdkError::result_or(
+            result,
+            DecklinkHardwareReferenceClock {
+                hardware_time,
+                time_in_frame,
+                ticks_per_frame,
+            },
+        )
+    }
+}
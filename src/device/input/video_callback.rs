@@ -0,0 +1,160 @@
+use crate::callback::run_callback;
+use crate::device::input::audio::DecklinkAudioInputPacket;
+use crate::device::input::enums::{
+    DecklinkDetectedVideoInputFormatFlags, DecklinkVideoInputFormatChangedEvents,
+};
+use crate::display_mode::{wrap_display_mode, DecklinkDisplayMode};
+use crate::frame::DecklinkVideoInputFrame;
+use crate::{sdk, SdkError};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+
+pub(crate) fn free_callback_wrapper(wrapper: *mut CallbackWrapper) {
+    unsafe {
+        drop(Box::from_raw(wrapper));
+    }
+}
+
+pub(crate) fn register_callback(
+    dev: *mut sdk::cdecklink_input_t,
+) -> Result<*mut CallbackWrapper, SdkError> {
+    let callback_wrapper = Box::into_raw(Box::new(CallbackWrapper {
+        handler: RwLock::new(None),
+        audio_sample_frame_bytes: AtomicUsize::new(0),
+    }));
+
+    let result = unsafe {
+        sdk::cdecklink_input_set_callback(
+            dev,
+            callback_wrapper as *mut std::ffi::c_void,
+            Some(video_input_format_changed),
+            Some(video_input_frame_arrived),
+        )
+    };
+
+    match SdkError::result_or(result, callback_wrapper) {
+        Err(e) => {
+            free_callback_wrapper(callback_wrapper);
+            Err(e)
+        }
+        Ok(v) => Ok(v),
+    }
+}
+
+/// The handler is invoked from the sdk's capture thread, so must be safe to share with it
+pub trait DeckLinkVideoInputCallback: Send + Sync {
+    fn video_input_format_changed(
+        &self,
+        events: DecklinkVideoInputFormatChangedEvents,
+        display_mode: Option<DecklinkDisplayMode>,
+        detected_signal_flags: DecklinkDetectedVideoInputFormatFlags,
+    ) -> bool;
+    fn video_input_frame_arrived(
+        &self,
+        video_frame: Option<DecklinkVideoInputFrame>,
+        audio_packet: Option<DecklinkAudioInputPacket>,
+    ) -> bool;
+}
+
+/// A closure can be used as the callback, to handle the captured frames.
+/// Format changes are ignored.
+impl<F> DeckLinkVideoInputCallback for F
+where
+    F: Fn(Option<DecklinkVideoInputFrame>, Option<DecklinkAudioInputPacket>) -> bool + Send + Sync,
+{
+    fn video_input_format_changed(
+        &self,
+        _events: DecklinkVideoInputFormatChangedEvents,
+        _display_mode: Option<DecklinkDisplayMode>,
+        _detected_signal_flags: DecklinkDetectedVideoInputFormatFlags,
+    ) -> bool {
+        true
+    }
+    fn video_input_frame_arrived(
+        &self,
+        video_frame: Option<DecklinkVideoInputFrame>,
+        audio_packet: Option<DecklinkAudioInputPacket>,
+    ) -> bool {
+        self(video_frame, audio_packet)
+    }
+}
+
+pub(crate) struct CallbackWrapper {
+    pub handler: RwLock<Option<Arc<dyn DeckLinkVideoInputCallback>>>,
+    /// The size of a single sample frame of the enabled audio input
+    pub audio_sample_frame_bytes: AtomicUsize,
+}
+impl CallbackWrapper {
+    /// Take a reference to the handler, so that the lock is not held while it runs.
+    /// Otherwise a handler which blocks would prevent the handler from being replaced.
+    fn current_handler(&self) -> Option<Arc<dyn DeckLinkVideoInputCallback>> {
+        match self.handler.read() {
+            Ok(handler) => handler.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+}
+extern "C" fn video_input_format_changed(
+    context: *mut ::std::os::raw::c_void,
+    events: sdk::DecklinkVideoInputFormatChangedEvents,
+    display_mode: *mut sdk::cdecklink_display_mode_t,
+    detected_signal_flags: sdk::DecklinkDetectedVideoInputFormatFlags,
+) -> sdk::HRESULT {
+    let wrapper: &CallbackWrapper = unsafe { &*(context as *const _) };
+
+    run_callback("video_input_format_changed", || {
+        match wrapper.current_handler() {
+            Some(handler) => {
+                let display_mode_internal = if display_mode.is_null() {
+                    None
+                } else {
+                    unsafe {
+                        sdk::cdecklink_display_mode_add_ref(display_mode);
+                        Some(wrap_display_mode(display_mode))
+                    }
+                };
+
+                handler.video_input_format_changed(
+                    DecklinkVideoInputFormatChangedEvents::from_bits_retain(events),
+                    display_mode_internal,
+                    DecklinkDetectedVideoInputFormatFlags::from_bits_retain(detected_signal_flags),
+                )
+            }
+            None => true,
+        }
+    })
+}
+extern "C" fn video_input_frame_arrived(
+    context: *mut ::std::os::raw::c_void,
+    video_frame: *mut sdk::cdecklink_video_input_frame_t,
+    audio_packet: *mut sdk::cdecklink_audio_input_packet_t,
+) -> sdk::HRESULT {
+    let wrapper: &CallbackWrapper = unsafe { &*(context as *const _) };
+
+    run_callback("video_input_frame_arrived", || {
+        match wrapper.current_handler() {
+            Some(handler) => {
+                let video_frame_internal = if video_frame.is_null() {
+                    None
+                } else {
+                    unsafe { Some(DecklinkVideoInputFrame::from(video_frame)) }
+                };
+                let audio_packet_internal = if audio_packet.is_null() {
+                    None
+                } else {
+                    let sample_frame_bytes =
+                        wrapper.audio_sample_frame_bytes.load(Ordering::Relaxed);
+                    unsafe {
+                        Some(DecklinkAudioInputPacket::from(
+                            audio_packet,
+                            sample_frame_bytes,
+                        ))
+                    }
+                };
+
+                handler.video_input_frame_arrived(video_frame_internal, audio_packet_internal)
+            }
+            None => true,
+        }
+    })
+}
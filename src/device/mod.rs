@@ -1,8 +1,12 @@
 use crate::device::attributes::DecklinkDeviceAttributes;
-use crate::device::notification::DecklinkDeviceNotification;
+use crate::device::capabilities::CapabilityMatrix;
+use crate::device::input::DecklinkInputDevice;
+use crate::device::notification::{wrap_notification, DecklinkDeviceNotification};
 use crate::device::output::DecklinkOutputDevice;
 use crate::device::status::DecklinkDeviceStatus;
-use crate::display_mode::{DecklinkDisplayMode, DecklinkDisplayModeId};
+use crate::display_mode::{
+    find_display_mode, DecklinkDisplayMode, DecklinkDisplayModeId, DisplayModeScan,
+};
 use crate::frame::DecklinkPixelFormat;
 use crate::sdk;
 use crate::util::{convert_and_release_c_string, SdkError};
@@ -10,9 +14,14 @@ use std::ptr::{null, null_mut};
 use std::sync::{Arc, Mutex, Weak};
 
 pub mod attributes;
+pub mod capabilities;
+pub mod info;
+pub mod input;
 pub mod notification;
 pub mod output;
 pub mod status;
+mod teardown;
+pub mod topology;
 
 pub struct DecklinkDevice {
     dev: *mut crate::sdk::cdecklink_device_t,
@@ -29,7 +38,22 @@ impl Drop for DecklinkDevice {
     }
 }
 
-#[derive(FromPrimitive, PartialEq)]
+impl Clone for DecklinkDevice {
+    fn clone(&self) -> Self {
+        unsafe { sdk::cdecklink_device_add_ref(self.dev) };
+        let notification = match self.notification.lock() {
+            Ok(notification) => notification.clone(),
+            Err(_) => Weak::new(),
+        };
+        DecklinkDevice {
+            dev: self.dev,
+            notification: Mutex::new(notification),
+        }
+    }
+}
+
+#[derive(FromPrimitive, PartialEq, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DecklinkDisplayModeSupport {
     NotSupported = sdk::_DecklinkDisplayModeSupport_decklinkDisplayModeNotSupported as isize,
     Supported = sdk::_DecklinkDisplayModeSupport_decklinkDisplayModeSupported as isize,
@@ -37,6 +61,18 @@ pub enum DecklinkDisplayModeSupport {
         sdk::_DecklinkDisplayModeSupport_decklinkDisplayModeSupportedWithConversion as isize,
 }
 
+/// A reading of the hardware reference clock, in the timescale it was requested in.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DecklinkHardwareReferenceClock {
+    /// The current time of the hardware reference clock.
+    pub hardware_time: i64,
+    /// The time elapsed since the start of the current frame.
+    pub time_in_frame: i64,
+    /// The duration of a single frame.
+    pub ticks_per_frame: i64,
+}
+
 pub trait DecklinkDeviceDisplayModes<T> {
     fn does_support_video_mode(
         &self,
@@ -46,6 +82,56 @@ pub trait DecklinkDeviceDisplayModes<T> {
     ) -> Result<(DecklinkDisplayModeSupport, Option<DecklinkDisplayMode>), SdkError>;
 
     fn display_modes(&self) -> Result<Vec<DecklinkDisplayMode>, SdkError>;
+
+    /// Find the display mode which best matches the given properties.
+    /// See `decklink::display_mode::find_display_mode` for how the match is made.
+    fn find_display_mode(
+        &self,
+        width: usize,
+        height: usize,
+        rate: f64,
+        scan: Option<DisplayModeScan>,
+    ) -> Result<Option<DecklinkDisplayMode>, SdkError> {
+        let modes = self.display_modes()?;
+        let found = find_display_mode(&modes, width, height, rate, scan).map(|m| m.mode());
+        Ok(found.and_then(|id| modes.into_iter().find(|m| m.mode() == id)))
+    }
+
+    /// The combinations of flags that are relevant to probe in `capability_matrix`.
+    /// By default only the empty set of flags is probed.
+    fn capability_flag_sets(&self) -> Vec<T>
+    where
+        T: bitflags::Flags,
+    {
+        vec![T::empty()]
+    }
+
+    /// Probe every display mode against every pixel format, for each of the `capability_flag_sets`.
+    /// This makes a driver call for every combination, so can take a while on devices with many modes.
+    fn capability_matrix(&self) -> Result<CapabilityMatrix<T>, SdkError>
+    where
+        T: Copy + bitflags::Flags,
+    {
+        self.capability_matrix_for(&self.capability_flag_sets())
+    }
+
+    /// Probe every display mode against every pixel format, for each of the given flag sets
+    fn capability_matrix_for(&self, flag_sets: &[T]) -> Result<CapabilityMatrix<T>, SdkError>
+    where
+        T: Copy,
+    {
+        let modes: Vec<DecklinkDisplayModeId> =
+            self.display_modes()?.iter().map(|m| m.mode()).collect();
+
+        Ok(CapabilityMatrix::probe(
+            &modes,
+            flag_sets,
+            |mode, pixel_format, flags| {
+                self.does_support_video_mode(mode, pixel_format, flags)
+                    .map(|(support, actual_mode)| (support, actual_mode.map(|m| m.mode())))
+            },
+        ))
+    }
 }
 
 impl DecklinkDevice {
@@ -79,13 +165,17 @@ impl DecklinkDevice {
         SdkError::result_or_else(r, || DecklinkDeviceStatus::from(s))
     }
     pub fn get_notification(&self) -> Result<Arc<DecklinkDeviceNotification>, SdkError> {
-        if let Ok(locked) = self.notification.lock() {
+        if let Ok(mut locked) = self.notification.lock() {
             if let Some(val) = locked.upgrade() {
                 Ok(val)
             } else {
-                // TODO
+                let mut s = null_mut();
+                let r = unsafe { sdk::cdecklink_device_query_notification(self.dev, &mut s) };
+                SdkError::result::<()>(r)?;
 
-                Err(SdkError::FALSE)
+                let val = wrap_notification(s);
+                *locked = Arc::downgrade(&val);
+                Ok(val)
             }
         } else {
             Err(SdkError::HANDLE)
@@ -99,7 +189,17 @@ impl DecklinkDevice {
         if !SdkError::is_ok(res) || output.is_null() {
             None
         } else {
-            Some(DecklinkOutputDevice::from(output))
+            Some(unsafe { DecklinkOutputDevice::from(self.dev, output) })
+        }
+    }
+
+    pub fn input(&self) -> Option<DecklinkInputDevice> {
+        let mut input = null_mut();
+        let res = unsafe { sdk::cdecklink_device_query_input(self.dev, &mut input) };
+        if !SdkError::is_ok(res) || input.is_null() {
+            None
+        } else {
+            unsafe { DecklinkInputDevice::from(self.dev, input) }.ok()
         }
     }
 }
@@ -117,6 +217,7 @@ pub fn get_devices() -> Result<Vec<DecklinkDevice>, SdkError> {
             if SdkError::is_false(ok) {
                 break;
             } else if SdkError::is_ok(ok) {
+                // The iterator hands over a reference to each device
                 res.push(DecklinkDevice {
                     dev,
                     notification: Mutex::new(Weak::new()),
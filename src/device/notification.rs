@@ -1,3 +1,4 @@
+use crate::callback::run_callback;
 use crate::device::status::DecklinkStatusId;
 use crate::{sdk, SdkError};
 use num_traits::FromPrimitive;
@@ -5,19 +6,33 @@ use std::ptr::null_mut;
 use std::sync::Arc;
 
 #[derive(FromPrimitive, PartialEq, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum NotificationTopic {
     PreferencesChanged = sdk::_DecklinkNotifications_decklinkPreferencesChanged as isize,
     StatusChanged = sdk::_DecklinkNotifications_decklinkStatusChanged as isize,
 }
 
-// pub(crate) fn wrap_notification(
-//     ptr: *mut sdk::cdecklink_status_t,
-// ) -> Arc<DecklinkDeviceNotification> {
-//     Arc::new(DecklinkDeviceNotification { dev: ptr })
-// }
+pub(crate) fn wrap_notification(
+    ptr: *mut sdk::cdecklink_notification_t,
+) -> Arc<DecklinkDeviceNotification> {
+    Arc::new(DecklinkDeviceNotification { dev: ptr })
+}
 
 pub struct DecklinkDeviceNotification {
-    dev: *mut sdk::cdecklink_status_t,
+    dev: *mut sdk::cdecklink_notification_t,
+}
+// The sdk object is reference counted, and subscribing and unsubscribing are safe from any thread.
+// This only holds the pointer, which is released once in `drop`.
+unsafe impl Send for DecklinkDeviceNotification {}
+unsafe impl Sync for DecklinkDeviceNotification {}
+
+impl Drop for DecklinkDeviceNotification {
+    fn drop(&mut self) {
+        if !self.dev.is_null() {
+            unsafe { sdk::cdecklink_notification_release(self.dev) };
+            self.dev = null_mut();
+        }
+    }
 }
 
 pub trait DecklinkDeviceNotificationExt {
@@ -78,9 +93,38 @@ impl Drop for DeckLinkNotificationCallbackHandle {
     }
 }
 
-pub trait DeckLinkNotificationCallback {
-    fn notify_status(&self, id: DecklinkStatusId) -> bool;
+/// The handler is invoked from the sdk's notification thread, so must be safe to share with it
+pub trait DeckLinkNotificationCallback: Send + Sync {
+    /// Called when a status value changes, for subscriptions to `NotificationTopic::StatusChanged`.
+    /// The new value can be read with `DecklinkDeviceStatus::get`.
+    fn notify_status(&self, _id: DecklinkStatusId) -> bool {
+        true
+    }
+
+    /// Called when the preferences have been changed in Desktop Video Setup, for subscriptions to `NotificationTopic::PreferencesChanged`.
+    fn notify_preferences_changed(&self) -> bool {
+        true
+    }
+
+    /// Called for every notification, before it is dispatched to the topic specific method.
+    /// This can be overridden to receive the raw parameters of the notification.
+    fn notify(&self, topic: NotificationTopic, param1: u64, _param2: u64) -> bool {
+        match topic {
+            NotificationTopic::StatusChanged => self.notify_status(DecklinkStatusId::from(param1)),
+            NotificationTopic::PreferencesChanged => self.notify_preferences_changed(),
+        }
+    }
 }
+/// A closure can be used as the callback, and receives the raw parameters of every notification
+impl<F> DeckLinkNotificationCallback for F
+where
+    F: Fn(NotificationTopic, u64, u64) -> bool + Send + Sync,
+{
+    fn notify(&self, topic: NotificationTopic, param1: u64, param2: u64) -> bool {
+        self(topic, param1, param2)
+    }
+}
+
 struct DecklinkNotificationWrapper {
     handler: Arc<dyn DeckLinkNotificationCallback>,
     topic: u32,
@@ -90,23 +134,16 @@ extern "C" fn notify_callback(
     context: *mut ::std::os::raw::c_void,
     topic: sdk::DecklinkNotifications,
     param1: u64,
-    _param2: u64,
+    param2: u64,
 ) -> sdk::HRESULT {
-    let wrapper: &mut DecklinkNotificationWrapper = unsafe { &mut *(context as *mut _) };
+    let wrapper: &DecklinkNotificationWrapper = unsafe { &*(context as *const _) };
 
-    let mut result = true;
-    if topic == wrapper.topic {
-        let status_id = DecklinkStatusId::from_u64(param1);
-        if let Some(status_id) = status_id {
-            result = wrapper.handler.notify_status(status_id)
-        } else {
-            // Unmapped id field. Ignore it
+    run_callback("notify", || {
+        if topic == wrapper.topic {
+            if let Some(topic) = NotificationTopic::from_u32(topic) {
+                return wrapper.handler.notify(topic, param1, param2);
+            }
         }
-    }
-
-    if result {
-        0 // Ok
-    } else {
-        1 // False
-    }
+        true
+    })
 }
@@ -1,24 +1,51 @@
+use crate::device::output::enums::{DecklinkAudioSampleRate, DecklinkAudioSampleType};
 use crate::device::output::DecklinkOutputDevicePtr;
+use crate::device::teardown::teardown_output_audio;
 use crate::{sdk, SdkError};
-use std::rc::Rc;
-use std::sync::atomic::Ordering;
+use std::sync::Arc;
 
 pub struct DecklinkOutputDeviceAudio {
-    ptr: Rc<DecklinkOutputDevicePtr>,
+    ptr: Arc<DecklinkOutputDevicePtr>,
+    sample_rate: DecklinkAudioSampleRate,
+    sample_type: DecklinkAudioSampleType,
+    channels: u32,
 }
 impl Drop for DecklinkOutputDeviceAudio {
     fn drop(&mut self) {
-        // TODO - this is not safe!
-        unsafe {
-            sdk::cdecklink_output_disable_audio_output(self.ptr.dev);
-            self.ptr.audio_active.store(false, Ordering::Relaxed)
-        }
+        // The output is kept alive by `ptr`, and the audio can be disabled independently of the video.
+        // No audio callback is registered, so there is nothing else to tear down.
+        teardown_output_audio(&*self.ptr, &self.ptr.audio_active);
     }
 }
 impl DecklinkOutputDeviceAudio {
-    pub(crate) fn from(ptr: &Rc<DecklinkOutputDevicePtr>) -> DecklinkOutputDeviceAudio {
-        DecklinkOutputDeviceAudio { ptr: ptr.clone() }
+    pub(crate) fn from(
+        ptr: &Arc<DecklinkOutputDevicePtr>,
+        sample_rate: DecklinkAudioSampleRate,
+        sample_type: DecklinkAudioSampleType,
+        channels: u32,
+    ) -> DecklinkOutputDeviceAudio {
+        DecklinkOutputDeviceAudio {
+            ptr: ptr.clone(),
+            sample_rate,
+            sample_type,
+            channels,
+        }
+    }
+
+    pub fn sample_rate(&self) -> DecklinkAudioSampleRate {
+        self.sample_rate
+    }
+    pub fn sample_type(&self) -> DecklinkAudioSampleType {
+        self.sample_type
+    }
+    pub fn channels(&self) -> u32 {
+        self.channels
     }
+    /// The size in bytes of a single interleaved sample frame
+    pub fn sample_frame_bytes(&self) -> usize {
+        (self.sample_type as usize / 8) * self.channels as usize
+    }
+
     //    pub fn write_audio_samples_sync(&self, )
     //    HRESULT cdecklink_output_write_audio_samples_sync(cdecklink_output_t *output, void *buffer,
     //    uint32_t sampleFrameCount, uint32_t *sampleFramesWritten);
@@ -36,9 +63,28 @@ impl DecklinkOutputDeviceAudio {
         }
     }
 
-    //    HRESULT cdecklink_output_schedule_audio_samples(cdecklink_output_t *output, void *buffer,
-    //    uint32_t sampleFrameCount, int64_t streamTime,
-    //    int64_t timeScale, uint32_t *sampleFramesWritten);
+    /// Schedule interleaved audio samples for playback at the given stream time.
+    /// Returns the number of sample frames that were accepted.
+    pub fn schedule_audio_samples(
+        &self,
+        buffer: &[u8],
+        stream_time: i64,
+        timescale: i64,
+    ) -> Result<u32, SdkError> {
+        let sample_frame_count = buffer.len() / self.sample_frame_bytes();
+        unsafe {
+            let mut written = 0;
+            let result = sdk::cdecklink_output_schedule_audio_samples(
+                self.ptr.dev,
+                buffer.as_ptr() as *mut _,
+                sample_frame_count as u32,
+                stream_time,
+                timescale,
+                &mut written,
+            );
+            SdkError::result_or(result, written)
+        }
+    }
 
     pub fn buffered_audio_sample_frame_count(&self) -> Result<u32, SdkError> {
         unsafe {
@@ -0,0 +1,304 @@
+use crate::device::output::audio::DecklinkOutputDeviceAudio;
+use crate::device::output::enums::{
+    DecklinkAudioOutputStreamType, DecklinkAudioSampleRate, DecklinkAudioSampleType,
+    DecklinkVideoOutputFlags,
+};
+use crate::device::output::video::DecklinkOutputDeviceVideoScheduled;
+use crate::device::output::video_callback::DeckLinkVideoOutputCallback;
+use crate::device::output::DecklinkOutputDevice;
+use crate::display_mode::DecklinkDisplayModeId;
+use crate::frame::{DecklinkCustomFrame, DecklinkFrameBase};
+use crate::SdkError;
+use std::fmt;
+use std::sync::Arc;
+
+/// A failed transition between output states.
+/// The state that the transition started from is handed back, so that it can be retried or torn down.
+pub struct OutputTransitionError<T> {
+    pub state: T,
+    pub error: SdkError,
+}
+
+impl<T> fmt::Debug for OutputTransitionError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OutputTransitionError")
+            .field("error", &self.error)
+            .finish()
+    }
+}
+
+impl<T> From<OutputTransitionError<T>> for SdkError {
+    fn from(value: OutputTransitionError<T>) -> Self {
+        value.error
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+struct AudioConfig {
+    sample_rate: DecklinkAudioSampleRate,
+    sample_type: DecklinkAudioSampleType,
+    channels: u32,
+    stream_type: DecklinkAudioOutputStreamType,
+}
+
+/// The configuration of an output which has not been enabled yet, created by `DecklinkOutputDevice::configure`
+pub struct OutputConfig {
+    output: DecklinkOutputDevice,
+    mode: DecklinkDisplayModeId,
+    flags: DecklinkVideoOutputFlags,
+    audio: Option<AudioConfig>,
+}
+
+impl OutputConfig {
+    pub(crate) fn new(
+        output: DecklinkOutputDevice,
+        mode: DecklinkDisplayModeId,
+        flags: DecklinkVideoOutputFlags,
+    ) -> OutputConfig {
+        OutputConfig {
+            output,
+            mode,
+            flags,
+            audio: None,
+        }
+    }
+
+    pub fn mode(&self) -> DecklinkDisplayModeId {
+        self.mode
+    }
+    pub fn flags(&self) -> DecklinkVideoOutputFlags {
+        self.flags
+    }
+
+    /// Enable audio output alongside the video
+    pub fn with_audio(
+        mut self,
+        sample_rate: DecklinkAudioSampleRate,
+        sample_type: DecklinkAudioSampleType,
+        channels: u32,
+        stream_type: DecklinkAudioOutputStreamType,
+    ) -> OutputConfig {
+        self.audio = Some(AudioConfig {
+            sample_rate,
+            sample_type,
+            channels,
+            stream_type,
+        });
+        self
+    }
+
+    /// Remove any audio output from the configuration
+    pub fn without_audio(mut self) -> OutputConfig {
+        self.audio = None;
+        self
+    }
+
+    /// Enable the output for scheduled playback, with all times expressed in `timescale`.
+    /// The output starts out prerolling, ready for the first frames to be scheduled.
+    pub fn scheduled(
+        self,
+        timescale: i64,
+    ) -> Result<PrerollingOutput, OutputTransitionError<OutputConfig>> {
+        let video = match self
+            .output
+            .enable_video_output_scheduled(self.mode, self.flags, timescale)
+        {
+            Ok(video) => video,
+            Err(error) => return Err(OutputTransitionError { state: self, error }),
+        };
+
+        let audio = match self.audio {
+            Some(config) => match self.output.enable_audio_output(
+                config.sample_rate,
+                config.sample_type,
+                config.channels,
+                config.stream_type,
+            ) {
+                Ok(audio) => Some(audio),
+                Err(error) => {
+                    drop(video);
+                    return Err(OutputTransitionError { state: self, error });
+                }
+            },
+            None => None,
+        };
+
+        let output = PrerollingOutput {
+            config: self,
+            video,
+            audio,
+        };
+        match output.begin_preroll() {
+            Ok(()) => Ok(output),
+            Err(error) => Err(OutputTransitionError {
+                state: output.disable(),
+                error,
+            }),
+        }
+    }
+}
+
+/// A scheduled output that has been enabled, but is not playing yet.
+/// Frames and audio can be scheduled ahead of starting playback.
+pub struct PrerollingOutput {
+    config: OutputConfig,
+    video: Box<dyn DecklinkOutputDeviceVideoScheduled>,
+    audio: Option<DecklinkOutputDeviceAudio>,
+}
+
+impl PrerollingOutput {
+    fn begin_preroll(&self) -> Result<(), SdkError> {
+        match &self.audio {
+            Some(audio) => audio.begin_audio_preroll(),
+            None => Ok(()),
+        }
+    }
+
+    pub fn video(&self) -> &dyn DecklinkOutputDeviceVideoScheduled {
+        self.video.as_ref()
+    }
+    pub fn audio(&self) -> Option<&DecklinkOutputDeviceAudio> {
+        self.audio.as_ref()
+    }
+
+    pub fn set_callback(
+        &mut self,
+        handler: Option<Arc<dyn DeckLinkVideoOutputCallback>>,
+    ) -> Result<(), SdkError> {
+        self.video.set_callback(handler)
+    }
+
+    pub fn schedule_frame_copy(
+        &self,
+        frame: &dyn DecklinkFrameBase,
+        display_time: i64,
+        duration: i64,
+    ) -> Result<(), SdkError> {
+        self.video
+            .schedule_frame_copy(frame, display_time, duration)
+    }
+
+    pub fn schedule_custom_frame(
+        &self,
+        frame: DecklinkCustomFrame,
+        display_time: i64,
+        duration: i64,
+    ) -> Result<(), SdkError> {
+        self.video
+            .schedule_custom_frame(frame, display_time, duration)
+    }
+
+    /// Finish the preroll, and start playback from `start_time`
+    pub fn start(
+        mut self,
+        start_time: i64,
+        speed: f64,
+    ) -> Result<RunningOutput, OutputTransitionError<PrerollingOutput>> {
+        if let Some(audio) = &self.audio {
+            if let Err(error) = audio.end_audio_preroll() {
+                return Err(OutputTransitionError { state: self, error });
+            }
+        }
+
+        match self.video.start_playback(start_time, speed) {
+            Ok(()) => Ok(RunningOutput { inner: self }),
+            Err(error) => Err(OutputTransitionError { state: self, error }),
+        }
+    }
+
+    /// Disable the output, and hand back the configuration so that it can be enabled again.
+    /// Any scheduled frames are discarded.
+    pub fn disable(self) -> OutputConfig {
+        let PrerollingOutput {
+            config,
+            video,
+            audio,
+        } = self;
+        // Disabling the video blocks until all frame callbacks are complete, so do that before the audio
+        drop(video);
+        drop(audio);
+        config
+    }
+}
+
+/// The state handed back when `RunningOutput::stop` fails
+pub enum OutputStopState {
+    /// Playback could not be stopped, so is still running
+    Running(RunningOutput),
+    /// Playback stopped at the given time, but the audio preroll could not be started again.
+    /// The preroll can be retried with `audio().begin_audio_preroll()`, or the output disabled.
+    Stopped(PrerollingOutput, i64),
+}
+
+/// A scheduled output that is playing
+pub struct RunningOutput {
+    inner: PrerollingOutput,
+}
+
+impl RunningOutput {
+    pub fn video(&self) -> &dyn DecklinkOutputDeviceVideoScheduled {
+        self.inner.video()
+    }
+    pub fn audio(&self) -> Option<&DecklinkOutputDeviceAudio> {
+        self.inner.audio()
+    }
+
+    pub fn set_callback(
+        &mut self,
+        handler: Option<Arc<dyn DeckLinkVideoOutputCallback>>,
+    ) -> Result<(), SdkError> {
+        self.inner.set_callback(handler)
+    }
+
+    pub fn schedule_frame_copy(
+        &self,
+        frame: &dyn DecklinkFrameBase,
+        display_time: i64,
+        duration: i64,
+    ) -> Result<(), SdkError> {
+        self.inner
+            .schedule_frame_copy(frame, display_time, duration)
+    }
+
+    pub fn schedule_custom_frame(
+        &self,
+        frame: DecklinkCustomFrame,
+        display_time: i64,
+        duration: i64,
+    ) -> Result<(), SdkError> {
+        self.inner
+            .schedule_custom_frame(frame, display_time, duration)
+    }
+
+    /// Stop playback at `stop_time`, and hand back the prerolling output so that playback can be started again.
+    /// Returns the time that playback actually stopped at.
+    pub fn stop(
+        mut self,
+        stop_time: i64,
+    ) -> Result<(PrerollingOutput, i64), OutputTransitionError<OutputStopState>> {
+        let actual_stop_time = match self.inner.video.stop_playback(stop_time) {
+            Ok(time) => time,
+            Err(error) => {
+                return Err(OutputTransitionError {
+                    state: OutputStopState::Running(self),
+                    error,
+                })
+            }
+        };
+
+        let output = self.inner;
+        match output.begin_preroll() {
+            Ok(()) => Ok((output, actual_stop_time)),
+            Err(error) => Err(OutputTransitionError {
+                state: OutputStopState::Stopped(output, actual_stop_time),
+                error,
+            }),
+        }
+    }
+
+    /// Stop playback immediately and disable the output, handing back the configuration
+    pub fn disable(self) -> OutputConfig {
+        // Dropping the video stops the playback if it is running
+        self.inner.disable()
+    }
+}
@@ -0,0 +1,202 @@
+use crate::device::output::enums::DecklinkOutputFrameCompletionResult;
+use crate::device::output::video::DecklinkOutputDeviceVideoScheduled;
+use crate::device::output::video_callback::DeckLinkVideoOutputCallback;
+use crate::frame::DecklinkVideoFrame;
+use crate::SdkError;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll, Waker};
+
+/// A frame completion reported by a scheduled output
+pub type OutputCompletion = (
+    DecklinkOutputFrameCompletionResult,
+    Option<DecklinkVideoFrame>,
+);
+
+struct CompletionState {
+    completions: VecDeque<OutputCompletion>,
+    /// The callback has been removed from the output, so no more completions will arrive
+    closed: bool,
+    playback_stopped: bool,
+    /// The task waiting on the stream, if any
+    stream_waker: Option<Waker>,
+    /// The tasks waiting in `wait_for_buffer_below`
+    buffer_wakers: Vec<Waker>,
+}
+
+struct CompletionShared {
+    capacity: usize,
+    state: Mutex<CompletionState>,
+    dropped: AtomicU64,
+}
+
+impl CompletionShared {
+    fn lock(&self) -> MutexGuard<'_, CompletionState> {
+        match self.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    fn wake_all(mut state: MutexGuard<'_, CompletionState>) {
+        let stream_waker = state.stream_waker.take();
+        let buffer_wakers = std::mem::take(&mut state.buffer_wakers);
+        drop(state);
+
+        if let Some(waker) = stream_waker {
+            waker.wake();
+        }
+        for waker in buffer_wakers {
+            waker.wake();
+        }
+    }
+}
+
+/// The callback installed on the output by `OutputCompletions::new`
+struct CompletionCallback {
+    shared: Arc<CompletionShared>,
+}
+
+impl Drop for CompletionCallback {
+    fn drop(&mut self) {
+        // The callback is dropped once it has been replaced or the output has been disabled
+        let mut state = self.shared.lock();
+        state.closed = true;
+        CompletionShared::wake_all(state);
+    }
+}
+
+impl DeckLinkVideoOutputCallback for CompletionCallback {
+    fn schedule_frame_completed_callback(
+        &self,
+        frame: Option<DecklinkVideoFrame>,
+        result: DecklinkOutputFrameCompletionResult,
+    ) -> bool {
+        let mut state = self.shared.lock();
+        if state.completions.len() >= self.shared.capacity {
+            state.completions.pop_front();
+            self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+        state.completions.push_back((result, frame));
+        CompletionShared::wake_all(state);
+        true
+    }
+
+    fn playback_stopped(&self) -> bool {
+        let mut state = self.shared.lock();
+        state.playback_stopped = true;
+        CompletionShared::wake_all(state);
+        true
+    }
+}
+
+/// The frame completions of a scheduled output as a `futures::Stream`.
+/// Only the most recent `capacity` completions are kept, so the stream does not need to be polled
+/// when it is only being used for `wait_for_buffer_below`.
+///
+/// The stream ends once the callback is replaced or the output is dropped.
+pub struct OutputCompletions {
+    shared: Arc<CompletionShared>,
+}
+
+impl OutputCompletions {
+    /// Install a callback on `video` which feeds the completion stream.
+    /// This replaces any existing callback.
+    pub fn new(
+        video: &mut dyn DecklinkOutputDeviceVideoScheduled,
+        capacity: usize,
+    ) -> Result<OutputCompletions, SdkError> {
+        let shared = Arc::new(CompletionShared {
+            capacity: capacity.max(1),
+            state: Mutex::new(CompletionState {
+                completions: VecDeque::with_capacity(capacity),
+                closed: false,
+                playback_stopped: false,
+                stream_waker: None,
+                buffer_wakers: Vec::new(),
+            }),
+            dropped: AtomicU64::new(0),
+        });
+
+        video.set_callback(Some(Arc::new(CompletionCallback {
+            shared: shared.clone(),
+        })))?;
+
+        Ok(OutputCompletions { shared })
+    }
+
+    /// Whether the output has reported that scheduled playback has stopped
+    pub fn is_playback_stopped(&self) -> bool {
+        self.shared.lock().playback_stopped
+    }
+
+    /// The number of completions discarded because they were not taken from the stream in time
+    pub fn dropped_count(&self) -> u64 {
+        self.shared.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Wait until the number of frames buffered in `video` is below `count`, and return the buffered count.
+    /// This is checked each time a frame completes, so playback must be running for the buffer to drain.
+    /// `video` must be the output that this stream was created for.
+    /// As the output handles are not `Sync`, the future must be awaited on a local task.
+    pub fn wait_for_buffer_below<'a>(
+        &'a self,
+        video: &'a dyn DecklinkOutputDeviceVideoScheduled,
+        count: u32,
+    ) -> WaitForBufferBelow<'a> {
+        WaitForBufferBelow {
+            shared: &self.shared,
+            video,
+            count,
+        }
+    }
+}
+
+impl futures::Stream for OutputCompletions {
+    type Item = OutputCompletion;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut state = self.shared.lock();
+        if let Some(completion) = state.completions.pop_front() {
+            Poll::Ready(Some(completion))
+        } else if state.closed {
+            Poll::Ready(None)
+        } else {
+            state.stream_waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+/// The future returned by `OutputCompletions::wait_for_buffer_below`
+pub struct WaitForBufferBelow<'a> {
+    shared: &'a CompletionShared,
+    video: &'a dyn DecklinkOutputDeviceVideoScheduled,
+    count: u32,
+}
+
+impl Future for WaitForBufferBelow<'_> {
+    type Output = Result<u32, SdkError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // Register before checking, so that a completion in between is not missed
+        let closed = {
+            let mut state = self.shared.lock();
+            if !state.closed && !state.buffer_wakers.iter().any(|w| w.will_wake(cx.waker())) {
+                state.buffer_wakers.push(cx.waker().clone());
+            }
+            state.closed
+        };
+
+        match self.video.buffered_video_frame_count() {
+            Ok(buffered) if buffered < self.count => Poll::Ready(Ok(buffered)),
+            // Nothing will wake this again
+            Ok(_) if closed => Poll::Ready(Err(SdkError::HANDLE)),
+            Ok(_) => Poll::Pending,
+            Err(e) => Poll::Ready(Err(e)),
+        }
+    }
+}
@@ -1,18 +1,73 @@
-use crate::sdk;
+use crate::device::output::enums::DecklinkVideoOutputFlags;
+use crate::device::output::video_callback::{
+    register_callback, unregister_callback, CallbackWrapper,
+};
+use crate::device::teardown::{OutputAudioOps, OutputVideoOps};
+use crate::display_mode::DecklinkDisplayModeId;
+use crate::{sdk, SdkError};
 use std::ptr::null_mut;
-use std::rc::Rc;
 use std::sync::atomic::AtomicBool;
 
+/// The output handle shared by the output device and the enabled video and audio.
+/// This holds a reference to the parent device, so that it outlives every handle derived from it.
 pub struct DecklinkOutputDevicePtr {
     pub(crate) dev: *mut crate::sdk::cdecklink_output_t,
-    pub video_active: Rc<AtomicBool>,
-    pub audio_active: Rc<AtomicBool>,
+    pub(crate) parent: *mut crate::sdk::cdecklink_device_t,
+    pub video_active: AtomicBool,
+    pub audio_active: AtomicBool,
 }
+// Safety: `dev` and `parent` are owned references, only released in `drop` once every handle sharing this is gone.
+// Through `&self` they are only passed to sdk calls, which the sdk allows from any thread,
+// and the only state mutated through `&self` is atomic.
+unsafe impl Send for DecklinkOutputDevicePtr {}
+unsafe impl Sync for DecklinkOutputDevicePtr {}
+
 impl Drop for DecklinkOutputDevicePtr {
     fn drop(&mut self) {
+        // The video and audio hold a reference to this, so have already been disabled
         if !self.dev.is_null() {
             unsafe { sdk::cdecklink_output_release(self.dev) };
             self.dev = null_mut();
         }
+        if !self.parent.is_null() {
+            unsafe { sdk::cdecklink_device_release(self.parent) };
+            self.parent = null_mut();
+        }
+    }
+}
+
+impl OutputVideoOps for DecklinkOutputDevicePtr {
+    type Callback = *mut CallbackWrapper;
+
+    fn register_callback(&self) -> Result<*mut CallbackWrapper, SdkError> {
+        register_callback(self)
+    }
+    fn unregister_callback(&self, callback: *mut CallbackWrapper) {
+        unsafe { unregister_callback(self, callback) }
+    }
+    fn enable_video(
+        &self,
+        mode: DecklinkDisplayModeId,
+        flags: DecklinkVideoOutputFlags,
+    ) -> Result<(), SdkError> {
+        let result = unsafe {
+            sdk::cdecklink_output_enable_video_output(self.dev, u32::from(mode), flags.bits())
+        };
+        SdkError::result(result)
+    }
+    fn stop_playback(&self, timescale: i64) {
+        let mut actual_stop = 0;
+        unsafe {
+            sdk::cdecklink_output_stop_scheduled_playback(self.dev, 0, &mut actual_stop, timescale);
+        }
+    }
+    fn disable_video(&self) {
+        unsafe { sdk::cdecklink_output_disable_video_output(self.dev) };
+    }
+}
+
+impl OutputAudioOps for DecklinkOutputDevicePtr {
+    fn disable_audio(&self) {
+        unsafe { sdk::cdecklink_output_disable_audio_output(self.dev) };
     }
 }
@@ -2,6 +2,7 @@ use crate::sdk;
 
 bitflags! {
     #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct DecklinkVideoOutputFlags: u32 {
         const VANC = sdk::_DecklinkVideoOutputFlags_decklinkVideoOutputVANC;
         const VITC = sdk::_DecklinkVideoOutputFlags_decklinkVideoOutputVITC;
@@ -11,15 +12,18 @@ bitflags! {
 }
 
 #[derive(FromPrimitive, PartialEq, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DecklinkAudioSampleRate {
     Rate48kHz = sdk::_DecklinkAudioSampleRate_decklinkAudioSampleRate48kHz as isize,
 }
 #[derive(FromPrimitive, PartialEq, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DecklinkAudioSampleType {
     Int16 = sdk::_DecklinkAudioSampleType_decklinkAudioSampleType16bitInteger as isize,
     Int32 = sdk::_DecklinkAudioSampleType_decklinkAudioSampleType32bitInteger as isize,
 }
 #[derive(FromPrimitive, PartialEq, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DecklinkAudioOutputStreamType {
     Continuous = sdk::_DecklinkAudioOutputStreamType_decklinkAudioOutputStreamContinuous as isize,
     ContinuousDontResample =
@@ -28,6 +32,7 @@ pub enum DecklinkAudioOutputStreamType {
 }
 
 #[derive(FromPrimitive, PartialEq, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DecklinkOutputFrameCompletionResult {
     Completed = sdk::_DecklinkOutputFrameCompletionResult_decklinkOutputFrameCompleted as isize,
     DisplayedLate =
@@ -35,3 +40,14 @@ pub enum DecklinkOutputFrameCompletionResult {
     Dropped = sdk::_DecklinkOutputFrameCompletionResult_decklinkOutputFrameDropped as isize,
     Flushed = sdk::_DecklinkOutputFrameCompletionResult_decklinkOutputFrameFlushed as isize,
 }
+
+bitflags! {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct DecklinkReferenceStatus: u32 {
+        /// The hardware does not have a reference input.
+        const NOT_SUPPORTED_BY_HARDWARE = sdk::_DecklinkReferenceStatus_decklinkReferenceNotSupportedByHardware;
+        /// The output is locked to the reference input.
+        const LOCKED = sdk::_DecklinkReferenceStatus_decklinkReferenceLocked;
+    }
+}
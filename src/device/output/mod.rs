@@ -1,11 +1,16 @@
 mod audio;
+mod builder;
+#[cfg(feature = "async")]
+mod completions;
 mod device;
 mod enums;
+mod playout;
+mod stats;
 mod video;
 mod video_callback;
 
 use crate::device::output::device::DecklinkOutputDevicePtr;
-use crate::device::output::video_callback::register_callback;
+use crate::device::teardown::enable_output_video;
 use crate::display_mode::{
     iterate_display_modes, wrap_display_mode, DecklinkDisplayMode, DecklinkDisplayModeId,
 };
@@ -13,21 +18,34 @@ use crate::frame::DecklinkPixelFormat;
 use crate::{sdk, SdkError};
 use num_traits::FromPrimitive;
 use std::ptr::null_mut;
-use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
 pub use crate::device::output::audio::DecklinkOutputDeviceAudio;
+pub use crate::device::output::builder::{
+    OutputConfig, OutputStopState, OutputTransitionError, PrerollingOutput, RunningOutput,
+};
+#[cfg(feature = "async")]
+pub use crate::device::output::completions::{
+    OutputCompletion, OutputCompletions, WaitForBufferBelow,
+};
 pub use crate::device::output::enums::*;
+pub use crate::device::output::playout::{
+    FrameSource, LateFramePolicy, ScheduledPlayout, ScheduledPlayoutOptions,
+};
+pub use crate::device::output::stats::{OutputStatsCollector, OutputStatsSnapshot};
 pub use crate::device::output::video::{
-    DecklinkOutputDeviceVideoScheduled, DecklinkOutputDeviceVideoSync,
+    DecklinkOutputClock, DecklinkOutputDeviceVideoScheduled, DecklinkOutputDeviceVideoSync,
 };
 pub use crate::device::output::video_callback::DeckLinkVideoOutputCallback;
 use crate::device::{DecklinkDeviceDisplayModes, DecklinkDisplayModeSupport};
 
 use self::video::DecklinkOutputDeviceVideoImpl;
 
+/// A handle to the output of a device. Clones share the same output, and the video and audio enabled on it.
+#[derive(Clone)]
 pub struct DecklinkOutputDevice {
-    ptr: Rc<DecklinkOutputDevicePtr>,
+    ptr: Arc<DecklinkOutputDevicePtr>,
 }
 
 impl DecklinkDeviceDisplayModes<enums::DecklinkVideoOutputFlags> for DecklinkOutputDevice {
@@ -42,8 +60,8 @@ impl DecklinkDeviceDisplayModes<enums::DecklinkVideoOutputFlags> for DecklinkOut
         let result = unsafe {
             sdk::cdecklink_output_does_support_video_mode(
                 self.ptr.dev,
-                mode as u32,
-                pixel_format as u32,
+                u32::from(mode),
+                u32::from(pixel_format),
                 flags.bits(),
                 &mut supported,
                 &mut display_mode,
@@ -60,6 +78,15 @@ impl DecklinkDeviceDisplayModes<enums::DecklinkVideoOutputFlags> for DecklinkOut
         })
     }
 
+    fn capability_flag_sets(&self) -> Vec<enums::DecklinkVideoOutputFlags> {
+        vec![
+            enums::DecklinkVideoOutputFlags::empty(),
+            enums::DecklinkVideoOutputFlags::VANC,
+            enums::DecklinkVideoOutputFlags::RP188,
+            enums::DecklinkVideoOutputFlags::DUAL_STREAM_3D,
+        ]
+    }
+
     fn display_modes(&self) -> Result<Vec<DecklinkDisplayMode>, SdkError> {
         unsafe {
             let mut it = null_mut();
@@ -74,33 +101,38 @@ impl DecklinkDeviceDisplayModes<enums::DecklinkVideoOutputFlags> for DecklinkOut
         }
     }
 }
-// TODO - this is currently a bag of methods, and it could do with some more sanity checking (eg allow schedule when video not enabled etc)
+// These only check that the video and audio are not enabled twice. The order of the other calls, such as
+// ending the audio preroll before starting playback, or scheduling audio alongside sync video, is left for the sdk to reject.
+// `configure` provides a checked alternative for scheduled playback.
 impl DecklinkOutputDevice {
-    pub(crate) fn from(ptr: *mut crate::sdk::cdecklink_output_t) -> DecklinkOutputDevice {
+    /// Wrap an output that was queried from `parent`. This takes ownership of `ptr`, and a new reference to `parent`
+    pub(crate) unsafe fn from(
+        parent: *mut crate::sdk::cdecklink_device_t,
+        ptr: *mut crate::sdk::cdecklink_output_t,
+    ) -> DecklinkOutputDevice {
+        sdk::cdecklink_device_add_ref(parent);
         DecklinkOutputDevice {
-            ptr: Rc::new(DecklinkOutputDevicePtr {
+            ptr: Arc::new(DecklinkOutputDevicePtr {
                 dev: ptr,
-                video_active: Rc::new(AtomicBool::new(false)),
-                audio_active: Rc::new(AtomicBool::new(false)),
+                parent,
+                video_active: AtomicBool::new(false),
+                audio_active: AtomicBool::new(false),
             }),
         }
     }
 
-    /* Video Output */
-
-    unsafe fn enable_video_output_inner(
+    /// Start configuring the output for scheduled playback.
+    /// Each step of the returned builder only offers the calls that are valid in that state.
+    pub fn configure(
         &self,
         mode: DecklinkDisplayModeId,
         flags: enums::DecklinkVideoOutputFlags,
-    ) -> i32 {
-        if self.ptr.video_active.swap(true, Ordering::Relaxed) {
-            // TODO - better mode
-            SdkError::ACCESSDENIED as i32
-        } else {
-            sdk::cdecklink_output_enable_video_output(self.ptr.dev, mode as u32, flags.bits())
-        }
+    ) -> OutputConfig {
+        OutputConfig::new(self.clone(), mode, flags)
     }
 
+    /* Video Output */
+
     pub fn is_scheduled_playback_running(&self) -> Result<bool, SdkError> {
         unsafe {
             let mut running = false;
@@ -116,33 +148,24 @@ impl DecklinkOutputDevice {
         flags: enums::DecklinkVideoOutputFlags,
         timescale: i64,
     ) -> Result<Box<dyn DecklinkOutputDeviceVideoScheduled>, SdkError> {
-        match register_callback(&self.ptr) {
-            // Don't do this if already running?
-            Err(e) => Err(e),
-            Ok(wrapper) => {
-                // TODO - this leaks on error
-                let result = unsafe { self.enable_video_output_inner(mode, flags) };
-                SdkError::result_or_else(result, || {
-                    let r: Box<dyn DecklinkOutputDeviceVideoScheduled> = Box::new(
-                        DecklinkOutputDeviceVideoImpl::from(&self.ptr, wrapper, timescale),
-                    );
-                    r
-                })
-            }
-        }
+        // The callback must be in place before the output is enabled, so that no completions are missed
+        let wrapper = enable_output_video(&*self.ptr, &self.ptr.video_active, mode, flags, true)?
+            .unwrap_or(null_mut());
+        let r: Box<dyn DecklinkOutputDeviceVideoScheduled> = Box::new(
+            DecklinkOutputDeviceVideoImpl::from(&self.ptr, wrapper, timescale),
+        );
+        Ok(r)
     }
     pub fn enable_video_output_sync(
         &self,
         mode: DecklinkDisplayModeId,
         flags: enums::DecklinkVideoOutputFlags,
     ) -> Result<Box<dyn DecklinkOutputDeviceVideoSync>, SdkError> {
-        let result = unsafe { self.enable_video_output_inner(mode, flags) };
-        SdkError::result_or_else(result, || {
-            let r: Box<dyn DecklinkOutputDeviceVideoSync> = Box::new(
-                DecklinkOutputDeviceVideoImpl::from(&self.ptr, null_mut(), 1000),
-            );
-            r
-        })
+        enable_output_video(&*self.ptr, &self.ptr.video_active, mode, flags, false)?;
+        let r: Box<dyn DecklinkOutputDeviceVideoSync> = Box::new(
+            DecklinkOutputDeviceVideoImpl::from(&self.ptr, null_mut(), 1000),
+        );
+        Ok(r)
     }
 
     /* Audio Output */
@@ -166,7 +189,9 @@ impl DecklinkOutputDevice {
                     channels,
                     stream_type as u32,
                 );
-                SdkError::result_or_else(result, || DecklinkOutputDeviceAudio::from(&self.ptr))
+                SdkError::result_or_else(result, || {
+                    DecklinkOutputDeviceAudio::from(&self.ptr, sample_rate, sample_type, channels)
+                })
             }
         }
     }
@@ -0,0 +1,359 @@
+use crate::device::output::audio::DecklinkOutputDeviceAudio;
+use crate::device::output::enums::DecklinkOutputFrameCompletionResult;
+use crate::device::output::stats::OutputStatsCollector;
+use crate::device::output::video::DecklinkOutputDeviceVideoScheduled;
+use crate::device::output::video_callback::DeckLinkVideoOutputCallback;
+use crate::frame::{DecklinkFrameBase, DecklinkVideoFrame};
+use crate::SdkError;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, Weak};
+
+/// Supplies the frames and audio for a `ScheduledPlayout`.
+/// This is called from the decklink callback thread, so should not block for long.
+pub trait FrameSource: Send {
+    /// Produce the video frame to be displayed as `frame_number`.
+    /// Returning `None` repeats the previously scheduled frame.
+    fn next_video_frame(&mut self, frame_number: i64) -> Option<Box<dyn DecklinkFrameBase + Send>>;
+
+    /// Produce the interleaved audio samples to accompany `frame_number`.
+    /// `sample_frame_count` is the number of sample frames that keeps the audio in sync with the video.
+    fn next_audio_samples(
+        &mut self,
+        _frame_number: i64,
+        _sample_frame_count: usize,
+    ) -> Option<Vec<u8>> {
+        None
+    }
+}
+
+/// How to recover when the hardware reports a frame as late or dropped
+#[derive(PartialEq, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LateFramePolicy {
+    /// Leave a gap of one frame in the schedule, so that subsequent frames are scheduled further ahead.
+    /// The audio for the skipped slot is still scheduled, so that it stays continuous.
+    SkipAhead,
+    /// Schedule the last frame again, without consuming a frame from the source
+    RepeatLast,
+}
+
+#[derive(PartialEq, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScheduledPlayoutOptions {
+    /// The number of frames to keep buffered in the hardware
+    pub target_buffered_frames: u32,
+    pub late_frame_policy: LateFramePolicy,
+}
+impl Default for ScheduledPlayoutOptions {
+    fn default() -> Self {
+        Self {
+            target_buffered_frames: 4,
+            late_frame_policy: LateFramePolicy::SkipAhead,
+        }
+    }
+}
+
+struct PlayoutState {
+    // These are only taken during teardown
+    video: Option<Box<dyn DecklinkOutputDeviceVideoScheduled>>,
+    audio: Option<DecklinkOutputDeviceAudio>,
+
+    source: Box<dyn FrameSource>,
+    options: ScheduledPlayoutOptions,
+    frame_duration: i64,
+    timescale: i64,
+
+    next_frame_number: i64,
+    last_frame: Option<Box<dyn DecklinkFrameBase + Send>>,
+    /// Audio which the output has not accepted yet, along with the sample frame it starts at
+    pending_audio: VecDeque<(i64, Vec<u8>)>,
+    /// The slots of the frames that have been scheduled and not completed yet, in display order
+    scheduled_slots: VecDeque<i64>,
+
+    stats: Option<Arc<OutputStatsCollector>>,
+}
+
+impl PlayoutState {
+    /// The range of audio sample frames belonging to a video frame.
+    /// This is calculated from the absolute position, so that fractional frame rates do not drift.
+    fn audio_sample_range(
+        &self,
+        audio: &DecklinkOutputDeviceAudio,
+        frame_number: i64,
+    ) -> (i64, i64) {
+        let rate = audio.sample_rate() as i64 * self.frame_duration;
+        let start = frame_number * rate / self.timescale;
+        let end = (frame_number + 1) * rate / self.timescale;
+        (start, end)
+    }
+
+    /// Take the audio for `frame_number` from the source, and queue it to be scheduled
+    fn queue_audio(&mut self, frame_number: i64) {
+        let (start, end) = match &self.audio {
+            Some(audio) => self.audio_sample_range(audio, frame_number),
+            None => return,
+        };
+        if let Some(samples) = self
+            .source
+            .next_audio_samples(frame_number, (end - start) as usize)
+        {
+            self.pending_audio.push_back((start, samples));
+        }
+    }
+
+    /// Schedule as much of the queued audio as the output will accept.
+    /// Anything that is not accepted is kept, and retried when the next frame completes.
+    fn schedule_pending_audio(&mut self) -> Result<(), SdkError> {
+        let audio = match &self.audio {
+            Some(audio) => audio,
+            None => return Ok(()),
+        };
+        let sample_frame_bytes = audio.sample_frame_bytes().max(1);
+        let sample_rate = audio.sample_rate() as i64;
+
+        while let Some((position, samples)) = self.pending_audio.front_mut() {
+            let written = audio.schedule_audio_samples(samples, *position, sample_rate)? as usize;
+            let written_bytes = (written * sample_frame_bytes).min(samples.len());
+
+            // Any trailing partial sample frame can never be scheduled
+            if samples.len() - written_bytes < sample_frame_bytes {
+                self.pending_audio.pop_front();
+            } else {
+                samples.drain(..written_bytes);
+                *position += written as i64;
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn schedule_next(&mut self, repeat: bool) -> Result<bool, SdkError> {
+        let frame_number = self.next_frame_number;
+
+        if !repeat {
+            if let Some(frame) = self.source.next_video_frame(frame_number) {
+                self.last_frame = Some(frame);
+            }
+        }
+
+        let (video, frame) = match (&self.video, &self.last_frame) {
+            (Some(video), Some(frame)) => (video, frame),
+            // Nothing to display yet
+            _ => return Ok(false),
+        };
+
+        let display_time = frame_number * self.frame_duration;
+        video.schedule_frame_copy(frame.as_ref(), display_time, self.frame_duration)?;
+        self.scheduled_slots.push_back(frame_number);
+
+        self.queue_audio(frame_number);
+        self.next_frame_number += 1;
+        self.schedule_pending_audio()?;
+        Ok(true)
+    }
+
+    /// Leave the next slot in the schedule empty, keeping its audio
+    fn skip_slot(&mut self) {
+        self.queue_audio(self.next_frame_number);
+        self.next_frame_number += 1;
+    }
+
+    fn fill(&mut self) -> Result<(), SdkError> {
+        let buffered = match &self.video {
+            Some(video) => video.buffered_video_frame_count()?,
+            None => return Ok(()),
+        };
+
+        self.schedule_pending_audio()?;
+
+        for _ in buffered..self.options.target_buffered_frames {
+            if !self.schedule_next(false)? {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn record_stats(
+        &mut self,
+        frame: Option<&DecklinkVideoFrame>,
+        result: DecklinkOutputFrameCompletionResult,
+    ) {
+        // Frames complete in the order they are displayed
+        let slot = self.scheduled_slots.pop_front();
+
+        if let (Some(stats), Some(video)) = (&self.stats, &self.video) {
+            // The stats are kept in the output timescale
+            let timestamp = frame.and_then(|frame| {
+                video
+                    .frame_completion_reference_timestamp(frame, video.timescale())
+                    .ok()
+            });
+            let buffered = video.buffered_video_frame_count().ok();
+            match slot {
+                Some(slot) => stats.record_slot_completion(result, slot, timestamp, buffered),
+                None => stats.record_completion(result, timestamp, buffered),
+            }
+        }
+    }
+
+    fn frame_completed(
+        &mut self,
+        frame: Option<&DecklinkVideoFrame>,
+        result: DecklinkOutputFrameCompletionResult,
+    ) {
+        self.record_stats(frame, result);
+
+        match result {
+            DecklinkOutputFrameCompletionResult::Completed => {}
+            DecklinkOutputFrameCompletionResult::Flushed => return,
+            DecklinkOutputFrameCompletionResult::DisplayedLate
+            | DecklinkOutputFrameCompletionResult::Dropped => {
+                match self.options.late_frame_policy {
+                    LateFramePolicy::SkipAhead => self.skip_slot(),
+                    LateFramePolicy::RepeatLast => {
+                        // Errors will be retried by the fill below
+                        let _ = self.schedule_next(true);
+                    }
+                }
+            }
+        }
+
+        // There is nobody to report an error to here, the next completion will try again
+        let _ = self.fill();
+    }
+}
+
+struct PlayoutCallback {
+    state: Weak<Mutex<PlayoutState>>,
+}
+impl DeckLinkVideoOutputCallback for PlayoutCallback {
+    fn schedule_frame_completed_callback(
+        &self,
+        frame: Option<DecklinkVideoFrame>,
+        result: DecklinkOutputFrameCompletionResult,
+    ) -> bool {
+        if let Some(state) = self.state.upgrade() {
+            if let Ok(mut state) = state.lock() {
+                state.frame_completed(frame.as_ref(), result);
+            }
+        }
+        true
+    }
+
+    fn playback_stopped(&self) -> bool {
+        true
+    }
+}
+
+/// Drives a scheduled output from a `FrameSource`.
+/// This handles the preroll, keeps the hardware buffer topped up as frames complete, and recovers from late frames.
+pub struct ScheduledPlayout {
+    state: Arc<Mutex<PlayoutState>>,
+}
+
+impl Drop for ScheduledPlayout {
+    fn drop(&mut self) {
+        // Take the output out of the shared state, so that it is torn down on this thread and not inside a callback
+        let (video, audio) = match self.state.lock() {
+            Ok(mut state) => (state.video.take(), state.audio.take()),
+            Err(_) => (None, None),
+        };
+
+        if let Some(mut video) = video {
+            let _ = video.stop_playback(0);
+            // Dropping the video blocks until all frame callbacks are complete
+            drop(video);
+        }
+        drop(audio);
+    }
+}
+
+impl ScheduledPlayout {
+    /// Create a playout for an output that has been enabled with `enable_video_output_scheduled`.
+    /// `frame_duration` is the duration of one frame in the timescale of the output.
+    /// The state is shared with the decklink callback thread, so the source and output must be `Send`.
+    pub fn new(
+        mut video: Box<dyn DecklinkOutputDeviceVideoScheduled>,
+        audio: Option<DecklinkOutputDeviceAudio>,
+        source: Box<dyn FrameSource>,
+        frame_duration: i64,
+        options: ScheduledPlayoutOptions,
+    ) -> Result<ScheduledPlayout, SdkError> {
+        if frame_duration <= 0 || options.target_buffered_frames == 0 {
+            return Err(SdkError::INVALIDARG);
+        }
+
+        let state = Arc::new(Mutex::new(PlayoutState {
+            video: None,
+            audio,
+            source,
+            options,
+            frame_duration,
+            timescale: video.timescale(),
+            next_frame_number: 0,
+            last_frame: None,
+            pending_audio: VecDeque::new(),
+            scheduled_slots: VecDeque::new(),
+            stats: None,
+        }));
+
+        video.set_callback(Some(Arc::new(PlayoutCallback {
+            state: Arc::downgrade(&state),
+        })))?;
+        state.lock().map_err(|_| SdkError::HANDLE)?.video = Some(video);
+
+        Ok(ScheduledPlayout { state })
+    }
+
+    /// Attach a collector to gather statistics about the frame completions
+    pub fn set_stats(&self, stats: Option<Arc<OutputStatsCollector>>) -> Result<(), SdkError> {
+        let mut state = self.state.lock().map_err(|_| SdkError::HANDLE)?;
+        state.stats = stats;
+        Ok(())
+    }
+
+    /// Preroll the output to the target buffer depth, and start playback
+    pub fn start(&self) -> Result<(), SdkError> {
+        let mut state = self.state.lock().map_err(|_| SdkError::HANDLE)?;
+
+        if let Some(audio) = &state.audio {
+            audio.begin_audio_preroll()?;
+        }
+
+        state.fill()?;
+
+        if let Some(audio) = &state.audio {
+            audio.end_audio_preroll()?;
+        }
+
+        match &mut state.video {
+            Some(video) => video.start_playback(0, 1.0),
+            None => Err(SdkError::HANDLE),
+        }
+    }
+
+    /// Stop playback immediately
+    pub fn stop(&self) -> Result<(), SdkError> {
+        let mut state = self.state.lock().map_err(|_| SdkError::HANDLE)?;
+        match &mut state.video {
+            Some(video) => video.stop_playback(0).map(|_| ()),
+            None => Err(SdkError::HANDLE),
+        }
+    }
+
+    /// The number of frames that have been scheduled so far, including any skipped slots
+    pub fn scheduled_frame_count(&self) -> Result<i64, SdkError> {
+        let state = self.state.lock().map_err(|_| SdkError::HANDLE)?;
+        Ok(state.next_frame_number)
+    }
+
+    pub fn buffered_video_frame_count(&self) -> Result<u32, SdkError> {
+        let state = self.state.lock().map_err(|_| SdkError::HANDLE)?;
+        match &state.video {
+            Some(video) => video.buffered_video_frame_count(),
+            None => Err(SdkError::HANDLE),
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use crate::device::output::enums::DecklinkOutputFrameCompletionResult;
+use std::sync::Mutex;
+
+/// A point in time copy of the statistics gathered by an `OutputStatsCollector`.
+/// All times are in the timescale of the output.
+#[derive(PartialEq, Debug, Default, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OutputStatsSnapshot {
+    pub completed: u64,
+    pub displayed_late: u64,
+    pub dropped: u64,
+    pub flushed: u64,
+    /// The number of slots intentionally left empty in the schedule, such as by `LateFramePolicy::SkipAhead`
+    pub skipped_slots: u64,
+
+    /// The smallest number of buffered frames seen after a completion
+    pub buffered_min: Option<u32>,
+    /// The largest number of buffered frames seen after a completion
+    pub buffered_max: Option<u32>,
+
+    /// The number of completion intervals that the jitter figures are based on
+    pub jitter_samples: u64,
+    /// The mean difference between the completion interval and the expected interval.
+    /// The expected interval is one frame duration, plus one for each skipped slot in between.
+    pub jitter_mean: f64,
+    /// The largest difference between the completion interval and the expected interval
+    pub jitter_max: i64,
+}
+
+#[derive(Default)]
+struct StatsState {
+    snapshot: OutputStatsSnapshot,
+    jitter_total: i64,
+    last_completion_timestamp: Option<i64>,
+    last_completion_slot: Option<i64>,
+}
+
+/// Collects frame completion statistics for a scheduled output.
+/// This can be attached to a `ScheduledPlayout`, or fed manually from a `DeckLinkVideoOutputCallback`.
+pub struct OutputStatsCollector {
+    frame_duration: i64,
+    state: Mutex<StatsState>,
+}
+
+impl OutputStatsCollector {
+    /// Create a collector for an output where each frame lasts `frame_duration` in the output timescale
+    pub fn new(frame_duration: i64) -> OutputStatsCollector {
+        OutputStatsCollector {
+            frame_duration,
+            state: Mutex::new(StatsState::default()),
+        }
+    }
+
+    /// Record the completion of a frame, where frames are scheduled in consecutive slots.
+    /// `completion_timestamp` is the hardware reference time of the completion (see `frame_completion_reference_timestamp`),
+    /// and `buffered` is the number of frames still buffered in the hardware.
+    pub fn record_completion(
+        &self,
+        result: DecklinkOutputFrameCompletionResult,
+        completion_timestamp: Option<i64>,
+        buffered: Option<u32>,
+    ) {
+        self.record_completion_inner(result, None, completion_timestamp, buffered)
+    }
+
+    /// Record the completion of the frame scheduled in `slot`, which is its display time in frame durations.
+    /// Any slots left empty since the previous completion are not counted as jitter.
+    pub fn record_slot_completion(
+        &self,
+        result: DecklinkOutputFrameCompletionResult,
+        slot: i64,
+        completion_timestamp: Option<i64>,
+        buffered: Option<u32>,
+    ) {
+        self.record_completion_inner(result, Some(slot), completion_timestamp, buffered)
+    }
+
+    fn record_completion_inner(
+        &self,
+        result: DecklinkOutputFrameCompletionResult,
+        slot: Option<i64>,
+        completion_timestamp: Option<i64>,
+        buffered: Option<u32>,
+    ) {
+        let mut state = match self.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        match result {
+            DecklinkOutputFrameCompletionResult::Completed => state.snapshot.completed += 1,
+            DecklinkOutputFrameCompletionResult::DisplayedLate => {
+                state.snapshot.displayed_late += 1
+            }
+            DecklinkOutputFrameCompletionResult::Dropped => state.snapshot.dropped += 1,
+            DecklinkOutputFrameCompletionResult::Flushed => {
+                state.snapshot.flushed += 1;
+                // Flushed frames were never displayed, so have no meaningful timing
+                state.last_completion_timestamp = None;
+                state.last_completion_slot = None;
+                return;
+            }
+        }
+
+        if let Some(buffered) = buffered {
+            let snapshot = &mut state.snapshot;
+            snapshot.buffered_min =
+                Some(snapshot.buffered_min.map_or(buffered, |v| v.min(buffered)));
+            snapshot.buffered_max =
+                Some(snapshot.buffered_max.map_or(buffered, |v| v.max(buffered)));
+        }
+
+        let slots = match (slot, state.last_completion_slot) {
+            (Some(slot), Some(last)) if slot > last => slot - last,
+            _ => 1,
+        };
+        if slot.is_some() && state.last_completion_slot.is_some() {
+            state.snapshot.skipped_slots += (slots - 1) as u64;
+        }
+        state.last_completion_slot = slot;
+
+        if let Some(timestamp) = completion_timestamp {
+            if let Some(last) = state.last_completion_timestamp {
+                let jitter = (timestamp - last - self.frame_duration * slots).abs();
+                state.jitter_total += jitter;
+
+                let snapshot = &mut state.snapshot;
+                snapshot.jitter_samples += 1;
+                snapshot.jitter_max = snapshot.jitter_max.max(jitter);
+            }
+            state.last_completion_timestamp = Some(timestamp);
+
+            if state.snapshot.jitter_samples > 0 {
+                state.snapshot.jitter_mean =
+                    state.jitter_total as f64 / state.snapshot.jitter_samples as f64;
+            }
+        }
+    }
+
+    /// Get a copy of the current statistics
+    pub fn snapshot(&self) -> OutputStatsSnapshot {
+        match self.state.lock() {
+            Ok(state) => state.snapshot,
+            Err(poisoned) => poisoned.into_inner().snapshot,
+        }
+    }
+
+    /// Clear all the gathered statistics
+    pub fn reset(&self) {
+        let mut state = match self.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        *state = StatsState::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::output::enums::DecklinkOutputFrameCompletionResult as Result;
+
+    #[test]
+    fn counts_results_and_buffer_depth() {
+        let stats = OutputStatsCollector::new(1000);
+        stats.record_completion(Result::Completed, None, Some(4));
+        stats.record_completion(Result::DisplayedLate, None, Some(2));
+        stats.record_completion(Result::Dropped, None, Some(5));
+        stats.record_completion(Result::Flushed, None, Some(0));
+
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.completed, 1);
+        assert_eq!(snapshot.displayed_late, 1);
+        assert_eq!(snapshot.dropped, 1);
+        assert_eq!(snapshot.flushed, 1);
+        // Flushed completions are not part of playback
+        assert_eq!(snapshot.buffered_min, Some(2));
+        assert_eq!(snapshot.buffered_max, Some(5));
+
+        stats.reset();
+        assert_eq!(stats.snapshot(), OutputStatsSnapshot::default());
+    }
+
+    #[test]
+    fn jitter_of_consecutive_completions() {
+        let stats = OutputStatsCollector::new(1000);
+        for timestamp in [0, 1000, 2010, 2990, 4000] {
+            stats.record_completion(Result::Completed, Some(timestamp), None);
+        }
+
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.jitter_samples, 4);
+        assert_eq!(snapshot.jitter_max, 20);
+        assert_eq!(snapshot.jitter_mean, (0 + 10 + 20 + 10) as f64 / 4.0);
+    }
+
+    #[test]
+    fn skipped_slots_are_not_jitter() {
+        let stats = OutputStatsCollector::new(1000);
+        stats.record_slot_completion(Result::Completed, 0, Some(0), None);
+        stats.record_slot_completion(Result::DisplayedLate, 1, Some(1000), None);
+        // Slot 2 was left empty to recover from the late frame
+        stats.record_slot_completion(Result::Completed, 3, Some(3000), None);
+        stats.record_slot_completion(Result::Completed, 4, Some(4005), None);
+
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.skipped_slots, 1);
+        assert_eq!(snapshot.jitter_samples, 3);
+        assert_eq!(snapshot.jitter_max, 5);
+    }
+
+    #[test]
+    fn flush_restarts_timing() {
+        let stats = OutputStatsCollector::new(1000);
+        stats.record_slot_completion(Result::Completed, 0, Some(0), None);
+        stats.record_slot_completion(Result::Flushed, 1, None, None);
+        // Playback restarted much later, and from a different position
+        stats.record_slot_completion(Result::Completed, 10, Some(50_000), None);
+        stats.record_slot_completion(Result::Completed, 11, Some(51_000), None);
+
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.skipped_slots, 0);
+        assert_eq!(snapshot.jitter_samples, 1);
+        assert_eq!(snapshot.jitter_max, 0);
+    }
+}
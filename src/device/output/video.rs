@@ -1,10 +1,15 @@
+use crate::callback::run_callback;
+use crate::device::output::enums::DecklinkReferenceStatus;
 use crate::device::output::video_callback::{CallbackWrapper, DeckLinkVideoOutputCallback};
 use crate::device::output::DecklinkOutputDevicePtr;
-use crate::frame::{DecklinkAlignedVec, DecklinkFrameBase, DecklinkFrameBase2};
+use crate::device::teardown::teardown_output_video;
+use crate::device::DecklinkHardwareReferenceClock;
+use crate::frame::{
+    DecklinkCustomFrame, DecklinkFrameBase, DecklinkFrameBase2, DecklinkFrameBuffer,
+    DecklinkVideoFrame,
+};
 use crate::{sdk, SdkError};
 use std::ptr::null_mut;
-use std::rc::Rc;
-use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
 pub trait DecklinkOutputDeviceVideo {}
@@ -14,7 +19,7 @@ pub trait DecklinkOutputDeviceVideoSync: DecklinkOutputDeviceVideo {
     // TODO return type
     fn display_custom_frame(&self, frame: Box<dyn DecklinkFrameBase2>) -> Result<(), SdkError>;
 }
-pub trait DecklinkOutputDeviceVideoScheduled: DecklinkOutputDeviceVideo {
+pub trait DecklinkOutputDeviceVideoScheduled: DecklinkOutputDeviceVideo + Send {
     // TODO return type
     fn schedule_frame_copy(
         &self,
@@ -23,6 +28,15 @@ pub trait DecklinkOutputDeviceVideoScheduled: DecklinkOutputDeviceVideo {
         duration: i64,
     ) -> Result<(), SdkError>;
 
+    /// Schedule a frame without copying its pixel data.
+    /// The buffer is released once the frame has completed and the driver has finished with it.
+    fn schedule_custom_frame(
+        &self,
+        frame: DecklinkCustomFrame,
+        display_time: i64,
+        duration: i64,
+    ) -> Result<(), SdkError>;
+
     fn set_callback(
         &mut self,
         handler: Option<Arc<dyn DeckLinkVideoOutputCallback>>,
@@ -30,39 +44,70 @@ pub trait DecklinkOutputDeviceVideoScheduled: DecklinkOutputDeviceVideo {
 
     fn buffered_video_frame_count(&self) -> Result<u32, SdkError>;
 
+    /// The timescale that all scheduling times are expressed in
+    fn timescale(&self) -> i64;
+
     fn start_playback(&mut self, start_time: i64, speed: f64) -> Result<(), SdkError>;
     fn stop_playback(&mut self, stop_time: i64) -> Result<i64, SdkError>;
+
+    /// A handle to read the clocks of the output, which can be moved into the completion callback.
+    fn clock(&self) -> DecklinkOutputClock;
+
+    /// Get the current stream time and playback speed of scheduled playback, in the requested timescale.
+    fn scheduled_stream_time(&self, timescale: i64) -> Result<(i64, f64), SdkError> {
+        self.clock().scheduled_stream_time(timescale)
+    }
+    /// Get the current time of the hardware reference clock, in the requested timescale.
+    fn hardware_reference_clock(
+        &self,
+        timescale: i64,
+    ) -> Result<DecklinkHardwareReferenceClock, SdkError> {
+        self.clock().hardware_reference_clock(timescale)
+    }
+    /// Get the hardware reference time at which a frame completed, in the requested timescale.
+    /// This is only valid while handling the completion callback for the frame,
+    /// so the handler should call it on a `clock` it has captured.
+    fn frame_completion_reference_timestamp(
+        &self,
+        frame: &DecklinkVideoFrame,
+        timescale: i64,
+    ) -> Result<i64, SdkError> {
+        self.clock()
+            .frame_completion_reference_timestamp(frame, timescale)
+    }
+    /// Get the genlock status of the output.
+    fn reference_status(&self) -> Result<DecklinkReferenceStatus, SdkError>;
 }
 
 pub(crate) struct DecklinkOutputDeviceVideoImpl {
-    ptr: Rc<DecklinkOutputDevicePtr>,
+    ptr: Arc<DecklinkOutputDevicePtr>,
     pub callback_wrapper: *mut CallbackWrapper,
     pub scheduled_running: bool,
     pub scheduled_timescale: i64,
 }
+// Safety: `callback_wrapper` is either null, or owned solely by this handle until it is freed in `drop`,
+// after the output has been disabled. It is shared with the sdk's callback thread while the output is enabled,
+// so it must be `Send + Sync`, which is checked below and relies on the handlers being `Send + Sync`.
+unsafe impl Send for DecklinkOutputDeviceVideoImpl {}
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<CallbackWrapper>();
+};
+
 impl Drop for DecklinkOutputDeviceVideoImpl {
     fn drop(&mut self) {
-        // TODO - safety!
-        unsafe {
-            if self.scheduled_running {
-                let mut actual_stop = 0;
-                sdk::cdecklink_output_stop_scheduled_playback(
-                    self.ptr.dev,
-                    0,
-                    &mut actual_stop,
-                    self.scheduled_timescale,
-                );
-            }
-
-            // This call blocks until all frame callbacks are complete
-            sdk::cdecklink_output_disable_video_output(self.ptr.dev);
-            self.ptr.video_active.store(false, Ordering::Relaxed);
-
-            if !self.callback_wrapper.is_null() {
-                drop(Box::from_raw(self.callback_wrapper)); // Reclaim the box so it gets freed
-                self.callback_wrapper = null_mut();
-            }
-        }
+        // The output, and the device it belongs to, are kept alive by `ptr` until after this.
+        let callback = if self.callback_wrapper.is_null() {
+            None
+        } else {
+            Some(std::mem::replace(&mut self.callback_wrapper, null_mut()))
+        };
+        teardown_output_video(
+            &*self.ptr,
+            &self.ptr.video_active,
+            self.scheduled_running.then_some(self.scheduled_timescale),
+            callback,
+        );
     }
 }
 
@@ -91,43 +136,22 @@ impl DecklinkOutputDeviceVideoSync for DecklinkOutputDeviceVideoImpl {
     }
 
     fn display_custom_frame(&self, frame: Box<dyn DecklinkFrameBase2>) -> Result<(), SdkError> {
-        let mut decklink_frame = WrappedCustomFrame { ptr: null_mut() };
-        let result = unsafe {
-            sdk::cdecklink_custom_video_frame_create_frame(
-                frame.width() as i64,
-                frame.height() as i64,
-                frame.row_bytes() as i64,
-                frame.pixel_format() as u32,
-                frame.flags().bits(),
-                &mut decklink_frame.ptr,
-            )
-        };
-        SdkError::result(result)?;
-
-        if decklink_frame.ptr.is_null() {
-            Err(SdkError::FAIL)?;
-        }
-
-        let required_bytes = frame.row_bytes() * frame.height();
+        let width = frame.width();
+        let height = frame.height();
+        let row_bytes = frame.row_bytes();
+        let pixel_format = frame.pixel_format();
+        let flags = frame.flags();
         let bytes = frame.into_avec()?;
-        if bytes.len() < required_bytes {
-            Err(SdkError::INVALIDARG)?;
-        }
-        if required_bytes % 64 != 0 {
-            // Must be a multiple of 64 to be valid for avx512
-            Err(SdkError::INVALIDARG)?;
-        }
 
-        let context = LeakableVec::from(bytes);
-
-        unsafe {
-            sdk::cdecklink_custom_video_frame_set_bytes(
-                decklink_frame.ptr,
-                context.get_data_ptr(),
-                Some(free_vec),
-                context.into_raw_ptr(),
-            )
-        };
+        let frame = DecklinkCustomFrame::new(
+            width,
+            height,
+            row_bytes,
+            pixel_format,
+            flags,
+            Box::new(bytes),
+        )?;
+        let decklink_frame = create_custom_frame(frame)?;
 
         let result = unsafe {
             sdk::cdecklink_output_display_video_frame_sync(self.ptr.dev, decklink_frame.ptr)
@@ -173,6 +197,27 @@ impl DecklinkOutputDeviceVideoScheduled for DecklinkOutputDeviceVideoImpl {
         SdkError::result(result)
     }
 
+    fn schedule_custom_frame(
+        &self,
+        frame: DecklinkCustomFrame,
+        display_time: i64,
+        duration: i64,
+    ) -> Result<(), SdkError> {
+        let decklink_frame = create_custom_frame(frame)?;
+
+        // The driver holds its own reference to the frame until it completes
+        let result = unsafe {
+            sdk::cdecklink_output_schedule_video_frame(
+                self.ptr.dev,
+                decklink_frame.ptr,
+                display_time,
+                duration,
+                self.scheduled_timescale,
+            )
+        };
+        SdkError::result(result)
+    }
+
     fn set_callback(
         &mut self,
         handler: Option<Arc<dyn DeckLinkVideoOutputCallback>>,
@@ -182,7 +227,11 @@ impl DecklinkOutputDeviceVideoScheduled for DecklinkOutputDeviceVideoImpl {
         } else {
             unsafe {
                 let wrapper = &(*self.callback_wrapper);
-                *wrapper.handler.write().unwrap() = handler;
+                let mut current = match wrapper.handler.write() {
+                    Ok(current) => current,
+                    Err(poisoned) => poisoned.into_inner(),
+                };
+                *current = handler;
             }
             Ok(())
         }
@@ -197,6 +246,10 @@ impl DecklinkOutputDeviceVideoScheduled for DecklinkOutputDeviceVideoImpl {
         }
     }
 
+    fn timescale(&self) -> i64 {
+        self.scheduled_timescale
+    }
+
     fn start_playback(&mut self, start_time: i64, speed: f64) -> Result<(), SdkError> {
         if self.scheduled_running {
             Ok(())
@@ -233,11 +286,122 @@ impl DecklinkOutputDeviceVideoScheduled for DecklinkOutputDeviceVideoImpl {
             Err(SdkError::FALSE)
         }
     }
+
+    fn clock(&self) -> DecklinkOutputClock {
+        DecklinkOutputClock {
+            ptr: self.ptr.clone(),
+        }
+    }
+
+    fn reference_status(&self) -> Result<DecklinkReferenceStatus, SdkError> {
+        unsafe {
+            let mut status = 0;
+            let result = sdk::cdecklink_output_get_reference_status(self.ptr.dev, &mut status);
+            SdkError::result_or(result, DecklinkReferenceStatus::from_bits_retain(status))
+        }
+    }
+}
+
+/// Reads the clocks of an output. This can be cloned and moved into the completion callback,
+/// which is where `frame_completion_reference_timestamp` must be called.
+/// Each value is returned in the timescale that it is requested in.
+///
+/// # Examples
+///
+/// ```no_run
+/// use decklink::device::output::{
+///     DecklinkOutputDeviceVideoScheduled, DecklinkOutputFrameCompletionResult,
+/// };
+/// use decklink::frame::DecklinkVideoFrame;
+/// use std::sync::Arc;
+///
+/// fn log_completions(video: &mut dyn DecklinkOutputDeviceVideoScheduled) {
+///     let clock = video.clock();
+///     let timescale = video.timescale();
+///     video
+///         .set_callback(Some(Arc::new(
+///             move |frame: Option<DecklinkVideoFrame>, _result: DecklinkOutputFrameCompletionResult| {
+///                 if let Some(frame) = frame {
+///                     let completed = clock.frame_completion_reference_timestamp(&frame, timescale);
+///                     println!("Frame completed at {:?}", completed);
+///                 }
+///                 true
+///             },
+///         )))
+///         .unwrap();
+/// }
+/// ```
+#[derive(Clone)]
+pub struct DecklinkOutputClock {
+    ptr: Arc<DecklinkOutputDevicePtr>,
+}
+
+impl DecklinkOutputClock {
+    /// Get the current stream time and playback speed of scheduled playback
+    pub fn scheduled_stream_time(&self, timescale: i64) -> Result<(i64, f64), SdkError> {
+        unsafe {
+            let mut stream_time = 0;
+            let mut playback_speed = 0.0;
+            let result = sdk::cdecklink_output_get_scheduled_stream_time(
+                self.ptr.dev,
+                timescale,
+                &mut stream_time,
+                &mut playback_speed,
+            );
+            SdkError::result_or(result, (stream_time, playback_speed))
+        }
+    }
+
+    /// Get the current time of the hardware reference clock
+    pub fn hardware_reference_clock(
+        &self,
+        timescale: i64,
+    ) -> Result<DecklinkHardwareReferenceClock, SdkError> {
+        unsafe {
+            let mut hardware_time = 0;
+            let mut time_in_frame = 0;
+            let mut ticks_per_frame = 0;
+            let result = sdk::cdecklink_output_get_hardware_reference_clock(
+                self.ptr.dev,
+                timescale,
+                &mut hardware_time,
+                &mut time_in_frame,
+                &mut ticks_per_frame,
+            );
+            SdkError::result_or(
+                result,
+                DecklinkHardwareReferenceClock {
+                    hardware_time,
+                    time_in_frame,
+                    ticks_per_frame,
+                },
+            )
+        }
+    }
+
+    /// Get the hardware reference time at which a frame completed.
+    /// This is only valid while handling the completion callback for the frame.
+    pub fn frame_completion_reference_timestamp(
+        &self,
+        frame: &DecklinkVideoFrame,
+        timescale: i64,
+    ) -> Result<i64, SdkError> {
+        unsafe {
+            let mut timestamp = 0;
+            let result = sdk::cdecklink_output_get_frame_completion_reference_timestamp(
+                self.ptr.dev,
+                frame.get_cdecklink_ptr(),
+                timescale,
+                &mut timestamp,
+            );
+            SdkError::result_or(result, timestamp)
+        }
+    }
 }
 
 impl DecklinkOutputDeviceVideoImpl {
     pub(crate) fn from(
-        ptr: &Rc<DecklinkOutputDevicePtr>,
+        ptr: &Arc<DecklinkOutputDevicePtr>,
         wrapper: *mut CallbackWrapper,
         timescale: i64,
     ) -> DecklinkOutputDeviceVideoImpl {
@@ -260,7 +424,7 @@ impl DecklinkOutputDeviceVideoImpl {
                 frame.width() as i32,
                 frame.height() as i32,
                 frame.row_bytes() as i32,
-                frame.pixel_format() as u32,
+                u32::from(frame.pixel_format()),
                 frame.flags().bits(),
                 &mut c_frame,
             );
@@ -305,32 +469,55 @@ impl Drop for WrappedCustomFrame {
     }
 }
 
-struct LeakableVec {
-    vec: DecklinkAlignedVec,
-}
-trait LeakableVec2 {
-    fn into_raw_ptr(self) -> *mut ::std::os::raw::c_void;
-    fn get_data_ptr(&self) -> *mut ::std::os::raw::c_void;
-}
-impl LeakableVec2 for Box<LeakableVec> {
-    fn into_raw_ptr(self) -> *mut ::std::os::raw::c_void {
-        Box::<LeakableVec>::into_raw(self) as *mut _
-    }
-    fn get_data_ptr(&self) -> *mut ::std::os::raw::c_void {
-        self.vec.as_ptr() as *mut _
+/// Create an sdk frame which uses the buffer of `frame` as its pixel data.
+/// The buffer is released by the sdk once the last reference to the frame is released.
+fn create_custom_frame(frame: DecklinkCustomFrame) -> Result<WrappedCustomFrame, SdkError> {
+    let mut decklink_frame = WrappedCustomFrame { ptr: null_mut() };
+    let result = unsafe {
+        sdk::cdecklink_custom_video_frame_create_frame(
+            frame.width() as i64,
+            frame.height() as i64,
+            frame.row_bytes() as i64,
+            u32::from(frame.pixel_format()),
+            frame.flags().bits(),
+            &mut decklink_frame.ptr,
+        )
+    };
+    SdkError::result::<()>(result)?;
+
+    if decklink_frame.ptr.is_null() {
+        Err(SdkError::FAIL)?;
     }
-}
-impl LeakableVec {
-    pub fn from(vec: DecklinkAlignedVec) -> Box<LeakableVec> {
-        Box::new(LeakableVec { vec })
+
+    let buffer = frame.into_buffer();
+    let bytes = buffer.as_bytes().as_ptr() as *mut ::std::os::raw::c_void;
+    let context = Box::into_raw(Box::new(buffer));
+
+    let result = unsafe {
+        sdk::cdecklink_custom_video_frame_set_bytes(
+            decklink_frame.ptr,
+            bytes,
+            Some(free_frame_buffer),
+            context as *mut ::std::os::raw::c_void,
+        )
+    };
+    if !SdkError::is_ok(result) {
+        // The frame did not take the buffer, so it must be released here
+        unsafe { drop(Box::from_raw(context)) };
+        Err(SdkError::from(result))?;
     }
+
+    Ok(decklink_frame)
 }
 
-unsafe extern "C" fn free_vec(
+unsafe extern "C" fn free_frame_buffer(
     _ptr: *mut ::std::os::raw::c_void,
     context: *mut ::std::os::raw::c_void,
 ) {
-    let wrapper = Box::from_raw(context as *mut LeakableVec);
+    let buffer = Box::from_raw(context as *mut Box<dyn DecklinkFrameBuffer>);
 
-    drop(wrapper);
+    run_callback("free_frame_buffer", || {
+        buffer.release();
+        true
+    });
 }
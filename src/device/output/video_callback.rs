@@ -1,9 +1,10 @@
+use crate::callback::run_callback;
 use crate::device::output::enums::DecklinkOutputFrameCompletionResult;
 use crate::device::output::DecklinkOutputDevicePtr;
 use crate::frame::DecklinkVideoFrame;
 use crate::{sdk, SdkError};
 use num_traits::FromPrimitive;
-use std::rc::Rc;
+use std::ptr::null_mut;
 use std::sync::{Arc, RwLock};
 
 pub(crate) fn free_callback_wrapper(wrapper: *mut CallbackWrapper) {
@@ -12,9 +13,18 @@ pub(crate) fn free_callback_wrapper(wrapper: *mut CallbackWrapper) {
     }
 }
 
-pub fn register_callback(
-    ptr: &Rc<DecklinkOutputDevicePtr>,
-) -> Result<*mut CallbackWrapper, SdkError> {
+/// Remove the callback from the output, and free the wrapper.
+/// The caller must ensure that the callback is not running and will not be invoked again,
+/// such as by disabling the video output first.
+pub(crate) unsafe fn unregister_callback(
+    ptr: &DecklinkOutputDevicePtr,
+    wrapper: *mut CallbackWrapper,
+) {
+    sdk::cdecklink_output_set_scheduled_frame_completion_callback(ptr.dev, null_mut(), None, None);
+    free_callback_wrapper(wrapper);
+}
+
+pub fn register_callback(ptr: &DecklinkOutputDevicePtr) -> Result<*mut CallbackWrapper, SdkError> {
     let callback_wrapper = Box::into_raw(Box::new(CallbackWrapper {
         handler: RwLock::new(None),
     }));
@@ -37,7 +47,8 @@ pub fn register_callback(
     }
 }
 
-pub trait DeckLinkVideoOutputCallback {
+/// The handler is invoked from the sdk's callback thread, so must be safe to share with it
+pub trait DeckLinkVideoOutputCallback: Send + Sync {
     fn schedule_frame_completed_callback(
         &self,
         frame: Option<DecklinkVideoFrame>,
@@ -46,47 +57,65 @@ pub trait DeckLinkVideoOutputCallback {
     fn playback_stopped(&self) -> bool;
 }
 
+/// A closure can be used as the callback, to handle the frame completions
+impl<F> DeckLinkVideoOutputCallback for F
+where
+    F: Fn(Option<DecklinkVideoFrame>, DecklinkOutputFrameCompletionResult) -> bool + Send + Sync,
+{
+    fn schedule_frame_completed_callback(
+        &self,
+        frame: Option<DecklinkVideoFrame>,
+        result: DecklinkOutputFrameCompletionResult,
+    ) -> bool {
+        self(frame, result)
+    }
+    fn playback_stopped(&self) -> bool {
+        true
+    }
+}
+
 pub struct CallbackWrapper {
     pub handler: RwLock<Option<Arc<dyn DeckLinkVideoOutputCallback>>>,
 }
+impl CallbackWrapper {
+    /// Take a reference to the handler, so that the lock is not held while it runs
+    fn current_handler(&self) -> Option<Arc<dyn DeckLinkVideoOutputCallback>> {
+        match self.handler.read() {
+            Ok(handler) => handler.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+}
 extern "C" fn schedule_frame_completed_callback(
     context: *mut ::std::os::raw::c_void,
     frame: *mut sdk::cdecklink_video_frame_t,
     result: sdk::DecklinkOutputFrameCompletionResult,
 ) -> sdk::HRESULT {
-    let wrapper: &mut CallbackWrapper = unsafe { &mut *(context as *mut _) };
+    let wrapper: &CallbackWrapper = unsafe { &*(context as *const _) };
 
-    let mut res = true;
-    if let Some(handler) = &*wrapper.handler.read().unwrap() {
-        let frame_internal = if frame.is_null() {
-            None
-        } else {
-            unsafe { Some(DecklinkVideoFrame::from(frame)) }
-        };
+    run_callback("schedule_frame_completed", || {
+        match wrapper.current_handler() {
+            Some(handler) => {
+                let frame_internal = if frame.is_null() {
+                    None
+                } else {
+                    unsafe { Some(DecklinkVideoFrame::from(frame)) }
+                };
 
-        let result_internal = DecklinkOutputFrameCompletionResult::from_u32(result)
-            .unwrap_or(DecklinkOutputFrameCompletionResult::Completed);
-
-        res = handler.schedule_frame_completed_callback(frame_internal, result_internal);
-    }
+                let result_internal = DecklinkOutputFrameCompletionResult::from_u32(result)
+                    .unwrap_or(DecklinkOutputFrameCompletionResult::Completed);
 
-    if res {
-        0 // Ok
-    } else {
-        1 // False
-    }
+                handler.schedule_frame_completed_callback(frame_internal, result_internal)
+            }
+            None => true,
+        }
+    })
 }
 extern "C" fn playback_stopped(context: *mut ::std::os::raw::c_void) -> sdk::HRESULT {
-    let wrapper: &mut CallbackWrapper = unsafe { &mut *(context as *mut _) };
+    let wrapper: &CallbackWrapper = unsafe { &*(context as *const _) };
 
-    let mut result = true;
-    if let Some(handler) = &*wrapper.handler.read().unwrap() {
-        result = handler.playback_stopped();
-    }
-
-    if result {
-        0 // Ok
-    } else {
-        1 // False
-    }
+    run_callback("playback_stopped", || match wrapper.current_handler() {
+        Some(handler) => handler.playback_stopped(),
+        None => true,
+    })
 }
@@ -1,59 +1,182 @@
 use crate::display_mode::DecklinkDisplayModeId;
 use crate::frame::DecklinkPixelFormat;
+use crate::util::convert_and_release_c_string;
 use crate::{sdk, SdkError};
 use num_traits::FromPrimitive;
 use std::os::raw::c_void;
-use std::ptr::null_mut;
+use std::ptr::{null, null_mut};
+use strum::IntoEnumIterator;
 
 pub struct DecklinkDeviceStatus {
     dev: *mut sdk::cdecklink_status_t,
 }
+// The sdk object is reference counted, and only offers getters which are safe to call from any thread.
+// This is what allows the status to be read from a notification callback, which runs on the sdk's thread.
+// This only holds the pointer, which is released once in `drop`.
+unsafe impl Send for DecklinkDeviceStatus {}
+unsafe impl Sync for DecklinkDeviceStatus {}
 
-#[derive(FromPrimitive, PartialEq, Debug, Copy, Clone)]
+#[derive(EnumIter, PartialEq, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DecklinkStatusId {
     /// The detected video input mode (BMDDisplayMode), available on devices which support input format detection.
-    DetectedVideoInputMode = sdk::_DecklinkStatusID_decklinkStatusDetectedVideoInputMode as isize,
-    DetectedVideoInputFlags = sdk::_DecklinkStatusID_decklinkStatusDetectedVideoInputFlags as isize,
+    DetectedVideoInputMode,
+    DetectedVideoInputFlags,
     /// The current video input mode (BMDDisplayMode).
-    CurrentVideoInputMode = sdk::_DecklinkStatusID_decklinkStatusCurrentVideoInputMode as isize,
+    CurrentVideoInputMode,
     /// The current video input pixel format (BMDPixelFormat).
-    CurrentVideoInputPixelFormat =
-        sdk::_DecklinkStatusID_decklinkStatusCurrentVideoInputPixelFormat as isize,
+    CurrentVideoInputPixelFormat,
     /// The current video input flags (BMDDeckLinkVideoStatusFlags)
-    CurrentVideoInputFlags = sdk::_DecklinkStatusID_decklinkStatusCurrentVideoInputFlags as isize,
+    CurrentVideoInputFlags,
     /// The current video output mode (BMDDisplayMode).
-    CurrentVideoOutputMode = sdk::_DecklinkStatusID_decklinkStatusCurrentVideoOutputMode as isize,
+    CurrentVideoOutputMode,
     /// The current video output flags (BMDDeckLinkVideoStatusFlags).
-    CurrentVideoOutputFlags = sdk::_DecklinkStatusID_decklinkStatusCurrentVideoOutputFlags as isize,
+    CurrentVideoOutputFlags,
     /// PCIe link width, x1, x4, etc.
-    PCIExpressLinkWidth = sdk::_DecklinkStatusID_decklinkStatusPCIExpressLinkWidth as isize,
+    PCIExpressLinkWidth,
     /// PCIe link speed, Gen. 1, Gen. 2, etc.
-    PCIExpressLinkSpeed = sdk::_DecklinkStatusID_decklinkStatusPCIExpressLinkSpeed as isize,
+    PCIExpressLinkSpeed,
     /// The last video output pixel format (BMDPixelFormat).
-    LastVideoOutputPixelFormat =
-        sdk::_DecklinkStatusID_decklinkStatusLastVideoOutputPixelFormat as isize,
+    LastVideoOutputPixelFormat,
     /// The detected reference input mode (BMDDisplayMode), available on devices which support reference input format detection.
-    ReferenceSignalMode = sdk::_DecklinkStatusID_decklinkStatusReferenceSignalMode as isize,
+    ReferenceSignalMode,
     /// The detected reference input flags (BMDDeckLinkVideoStatusFlags), available on devices which support reference input format detection.
-    ReferenceSignalFlags = sdk::_DecklinkStatusID_decklinkStatusReferenceSignalFlags as isize,
-    DuplexMode = sdk::_DecklinkStatusID_decklinkStatusDuplexMode as isize,
+    ReferenceSignalFlags,
+    DuplexMode,
     /// The current busy state of the device. (See BMDDeviceBusyState for more information).
-    Busy = sdk::_DecklinkStatusID_decklinkStatusBusy as isize,
+    Busy,
     /// The interchangeable panel installed (BMDPanelType).
-    InterchangeablePanelType =
-        sdk::_DecklinkStatusID_decklinkStatusInterchangeablePanelType as isize,
+    InterchangeablePanelType,
 
     /// True if the video input signal is locked.
-    VideoInputSignalLocked = sdk::_DecklinkStatusID_decklinkStatusVideoInputSignalLocked as isize,
+    VideoInputSignalLocked,
     /// True if the reference input signal is locked.
-    ReferenceSignalLocked = sdk::_DecklinkStatusID_decklinkStatusReferenceSignalLocked as isize,
+    ReferenceSignalLocked,
 
     /// The received EDID of a connected HDMI sink device.
-    ReceivedEDID = sdk::_DecklinkStatusID_decklinkStatusReceivedEDID as isize,
+    /// This can be parsed with `decklink::edid::Edid::parse`.
+    ReceivedEDID,
+
+    /// A status not known to this version of the bindings
+    #[strum(disabled)]
+    Other(u64),
+}
+impl From<u64> for DecklinkStatusId {
+    fn from(value: u64) -> Self {
+        match u32::try_from(value) {
+            Ok(sdk::_DecklinkStatusID_decklinkStatusDetectedVideoInputMode) => {
+                Self::DetectedVideoInputMode
+            }
+            Ok(sdk::_DecklinkStatusID_decklinkStatusDetectedVideoInputFlags) => {
+                Self::DetectedVideoInputFlags
+            }
+            Ok(sdk::_DecklinkStatusID_decklinkStatusCurrentVideoInputMode) => {
+                Self::CurrentVideoInputMode
+            }
+            Ok(sdk::_DecklinkStatusID_decklinkStatusCurrentVideoInputPixelFormat) => {
+                Self::CurrentVideoInputPixelFormat
+            }
+            Ok(sdk::_DecklinkStatusID_decklinkStatusCurrentVideoInputFlags) => {
+                Self::CurrentVideoInputFlags
+            }
+            Ok(sdk::_DecklinkStatusID_decklinkStatusCurrentVideoOutputMode) => {
+                Self::CurrentVideoOutputMode
+            }
+            Ok(sdk::_DecklinkStatusID_decklinkStatusCurrentVideoOutputFlags) => {
+                Self::CurrentVideoOutputFlags
+            }
+            Ok(sdk::_DecklinkStatusID_decklinkStatusPCIExpressLinkWidth) => {
+                Self::PCIExpressLinkWidth
+            }
+            Ok(sdk::_DecklinkStatusID_decklinkStatusPCIExpressLinkSpeed) => {
+                Self::PCIExpressLinkSpeed
+            }
+            Ok(sdk::_DecklinkStatusID_decklinkStatusLastVideoOutputPixelFormat) => {
+                Self::LastVideoOutputPixelFormat
+            }
+            Ok(sdk::_DecklinkStatusID_decklinkStatusReferenceSignalMode) => {
+                Self::ReferenceSignalMode
+            }
+            Ok(sdk::_DecklinkStatusID_decklinkStatusReferenceSignalFlags) => {
+                Self::ReferenceSignalFlags
+            }
+            Ok(sdk::_DecklinkStatusID_decklinkStatusDuplexMode) => Self::DuplexMode,
+            Ok(sdk::_DecklinkStatusID_decklinkStatusBusy) => Self::Busy,
+            Ok(sdk::_DecklinkStatusID_decklinkStatusInterchangeablePanelType) => {
+                Self::InterchangeablePanelType
+            }
+            Ok(sdk::_DecklinkStatusID_decklinkStatusVideoInputSignalLocked) => {
+                Self::VideoInputSignalLocked
+            }
+            Ok(sdk::_DecklinkStatusID_decklinkStatusReferenceSignalLocked) => {
+                Self::ReferenceSignalLocked
+            }
+            Ok(sdk::_DecklinkStatusID_decklinkStatusReceivedEDID) => Self::ReceivedEDID,
+            _ => Self::Other(value),
+        }
+    }
+}
+impl From<DecklinkStatusId> for u64 {
+    fn from(value: DecklinkStatusId) -> Self {
+        match value {
+            DecklinkStatusId::DetectedVideoInputMode => {
+                sdk::_DecklinkStatusID_decklinkStatusDetectedVideoInputMode as u64
+            }
+            DecklinkStatusId::DetectedVideoInputFlags => {
+                sdk::_DecklinkStatusID_decklinkStatusDetectedVideoInputFlags as u64
+            }
+            DecklinkStatusId::CurrentVideoInputMode => {
+                sdk::_DecklinkStatusID_decklinkStatusCurrentVideoInputMode as u64
+            }
+            DecklinkStatusId::CurrentVideoInputPixelFormat => {
+                sdk::_DecklinkStatusID_decklinkStatusCurrentVideoInputPixelFormat as u64
+            }
+            DecklinkStatusId::CurrentVideoInputFlags => {
+                sdk::_DecklinkStatusID_decklinkStatusCurrentVideoInputFlags as u64
+            }
+            DecklinkStatusId::CurrentVideoOutputMode => {
+                sdk::_DecklinkStatusID_decklinkStatusCurrentVideoOutputMode as u64
+            }
+            DecklinkStatusId::CurrentVideoOutputFlags => {
+                sdk::_DecklinkStatusID_decklinkStatusCurrentVideoOutputFlags as u64
+            }
+            DecklinkStatusId::PCIExpressLinkWidth => {
+                sdk::_DecklinkStatusID_decklinkStatusPCIExpressLinkWidth as u64
+            }
+            DecklinkStatusId::PCIExpressLinkSpeed => {
+                sdk::_DecklinkStatusID_decklinkStatusPCIExpressLinkSpeed as u64
+            }
+            DecklinkStatusId::LastVideoOutputPixelFormat => {
+                sdk::_DecklinkStatusID_decklinkStatusLastVideoOutputPixelFormat as u64
+            }
+            DecklinkStatusId::ReferenceSignalMode => {
+                sdk::_DecklinkStatusID_decklinkStatusReferenceSignalMode as u64
+            }
+            DecklinkStatusId::ReferenceSignalFlags => {
+                sdk::_DecklinkStatusID_decklinkStatusReferenceSignalFlags as u64
+            }
+            DecklinkStatusId::DuplexMode => sdk::_DecklinkStatusID_decklinkStatusDuplexMode as u64,
+            DecklinkStatusId::Busy => sdk::_DecklinkStatusID_decklinkStatusBusy as u64,
+            DecklinkStatusId::InterchangeablePanelType => {
+                sdk::_DecklinkStatusID_decklinkStatusInterchangeablePanelType as u64
+            }
+            DecklinkStatusId::VideoInputSignalLocked => {
+                sdk::_DecklinkStatusID_decklinkStatusVideoInputSignalLocked as u64
+            }
+            DecklinkStatusId::ReferenceSignalLocked => {
+                sdk::_DecklinkStatusID_decklinkStatusReferenceSignalLocked as u64
+            }
+            DecklinkStatusId::ReceivedEDID => {
+                sdk::_DecklinkStatusID_decklinkStatusReceivedEDID as u64
+            }
+            DecklinkStatusId::Other(v) => v,
+        }
+    }
 }
 
 bitflags! {
     #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct DecklinkVideoStatusFlags: u32 {
         /// Progressive frames are encoded as PsF.
         const PSF = sdk::_DecklinkVideoStatusFlags_decklinkVideoStatusPsF;
@@ -63,6 +186,7 @@ bitflags! {
 }
 
 #[derive(FromPrimitive, PartialEq, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DecklinkDuplexStatus {
     /// Capable of simultaneous playback and capture.
     FullDuplex = sdk::_DecklinkDuplexStatus_decklinkDuplexStatusFullDuplex as isize,
@@ -74,6 +198,155 @@ pub enum DecklinkDuplexStatus {
     Inactive = sdk::_DecklinkDuplexStatus_decklinkDuplexStatusInactive as isize,
 }
 
+bitflags! {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct DecklinkDeviceBusyState: u32 {
+        const CAPTURE = sdk::_DecklinkDeviceBusyState_decklinkDeviceCaptureBusy;
+        const PLAYBACK = sdk::_DecklinkDeviceBusyState_decklinkDevicePlaybackBusy;
+        const SERIAL_PORT = sdk::_DecklinkDeviceBusyState_decklinkDeviceSerialPortBusy;
+    }
+}
+
+#[derive(PartialEq, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DecklinkPanelType {
+    NotDetected,
+    TeranexMiniSmartPanel,
+    /// A panel not known to this version of the bindings
+    Other(u32),
+}
+impl From<u32> for DecklinkPanelType {
+    fn from(value: u32) -> Self {
+        match value {
+            sdk::_DecklinkPanelType_decklinkPanelNotDetected => Self::NotDetected,
+            sdk::_DecklinkPanelType_decklinkPanelTeranexMiniSmartPanel => {
+                Self::TeranexMiniSmartPanel
+            }
+            v => Self::Other(v),
+        }
+    }
+}
+impl From<DecklinkPanelType> for u32 {
+    fn from(value: DecklinkPanelType) -> Self {
+        match value {
+            DecklinkPanelType::NotDetected => sdk::_DecklinkPanelType_decklinkPanelNotDetected,
+            DecklinkPanelType::TeranexMiniSmartPanel => {
+                sdk::_DecklinkPanelType_decklinkPanelTeranexMiniSmartPanel
+            }
+            DecklinkPanelType::Other(v) => v,
+        }
+    }
+}
+
+/// A status value, typed according to its `DecklinkStatusId`
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DecklinkStatusValue {
+    DisplayMode(DecklinkDisplayModeId),
+    PixelFormat(DecklinkPixelFormat),
+    VideoStatusFlags(DecklinkVideoStatusFlags),
+    DuplexStatus(DecklinkDuplexStatus),
+    BusyState(DecklinkDeviceBusyState),
+    PanelType(DecklinkPanelType),
+    Int(i64),
+    Flag(bool),
+    Float(f64),
+    String(String),
+    Bytes(Vec<u8>),
+}
+
+/// A copy of every status value of a device at a point in time.
+/// Each value is `None` if it is not supported by the device in its current state.
+#[derive(PartialEq, Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StatusSnapshot {
+    pub detected_video_input_mode: Option<DecklinkDisplayModeId>,
+    pub detected_video_input_flags: Option<DecklinkVideoStatusFlags>,
+    pub current_video_input_mode: Option<DecklinkDisplayModeId>,
+    pub current_video_input_pixel_format: Option<DecklinkPixelFormat>,
+    pub current_video_input_flags: Option<DecklinkVideoStatusFlags>,
+    pub current_video_output_mode: Option<DecklinkDisplayModeId>,
+    pub current_video_output_flags: Option<DecklinkVideoStatusFlags>,
+    pub pci_express_link_width: Option<u32>,
+    pub pci_express_link_speed: Option<u32>,
+    pub last_video_output_pixel_format: Option<DecklinkPixelFormat>,
+    pub reference_signal_mode: Option<DecklinkDisplayModeId>,
+    pub reference_signal_flags: Option<DecklinkVideoStatusFlags>,
+    pub duplex_mode: Option<DecklinkDuplexStatus>,
+    pub busy: Option<DecklinkDeviceBusyState>,
+    pub interchangeable_panel_type: Option<DecklinkPanelType>,
+    pub video_input_signal_locked: Option<bool>,
+    pub reference_signal_locked: Option<bool>,
+    pub received_edid: Option<Vec<u8>>,
+}
+
+impl StatusSnapshot {
+    /// Get the value of a status from the snapshot
+    pub fn get(&self, id: DecklinkStatusId) -> Option<DecklinkStatusValue> {
+        match id {
+            DecklinkStatusId::DetectedVideoInputMode => self
+                .detected_video_input_mode
+                .map(DecklinkStatusValue::DisplayMode),
+            DecklinkStatusId::DetectedVideoInputFlags => self
+                .detected_video_input_flags
+                .map(DecklinkStatusValue::VideoStatusFlags),
+            DecklinkStatusId::CurrentVideoInputMode => self
+                .current_video_input_mode
+                .map(DecklinkStatusValue::DisplayMode),
+            DecklinkStatusId::CurrentVideoInputPixelFormat => self
+                .current_video_input_pixel_format
+                .map(DecklinkStatusValue::PixelFormat),
+            DecklinkStatusId::CurrentVideoInputFlags => self
+                .current_video_input_flags
+                .map(DecklinkStatusValue::VideoStatusFlags),
+            DecklinkStatusId::CurrentVideoOutputMode => self
+                .current_video_output_mode
+                .map(DecklinkStatusValue::DisplayMode),
+            DecklinkStatusId::CurrentVideoOutputFlags => self
+                .current_video_output_flags
+                .map(DecklinkStatusValue::VideoStatusFlags),
+            DecklinkStatusId::PCIExpressLinkWidth => self
+                .pci_express_link_width
+                .map(|v| DecklinkStatusValue::Int(v as i64)),
+            DecklinkStatusId::PCIExpressLinkSpeed => self
+                .pci_express_link_speed
+                .map(|v| DecklinkStatusValue::Int(v as i64)),
+            DecklinkStatusId::LastVideoOutputPixelFormat => self
+                .last_video_output_pixel_format
+                .map(DecklinkStatusValue::PixelFormat),
+            DecklinkStatusId::ReferenceSignalMode => self
+                .reference_signal_mode
+                .map(DecklinkStatusValue::DisplayMode),
+            DecklinkStatusId::ReferenceSignalFlags => self
+                .reference_signal_flags
+                .map(DecklinkStatusValue::VideoStatusFlags),
+            DecklinkStatusId::DuplexMode => self.duplex_mode.map(DecklinkStatusValue::DuplexStatus),
+            DecklinkStatusId::Busy => self.busy.map(DecklinkStatusValue::BusyState),
+            DecklinkStatusId::InterchangeablePanelType => self
+                .interchangeable_panel_type
+                .map(DecklinkStatusValue::PanelType),
+            DecklinkStatusId::VideoInputSignalLocked => self
+                .video_input_signal_locked
+                .map(DecklinkStatusValue::Flag),
+            DecklinkStatusId::ReferenceSignalLocked => {
+                self.reference_signal_locked.map(DecklinkStatusValue::Flag)
+            }
+            DecklinkStatusId::ReceivedEDID => {
+                self.received_edid.clone().map(DecklinkStatusValue::Bytes)
+            }
+            DecklinkStatusId::Other(_) => None,
+        }
+    }
+
+    /// Get the ids of the statuses which differ between two snapshots
+    pub fn diff(&self, other: &StatusSnapshot) -> Vec<DecklinkStatusId> {
+        DecklinkStatusId::iter()
+            .filter(|id| self.get(*id) != other.get(*id))
+            .collect()
+    }
+}
+
 impl Drop for DecklinkDeviceStatus {
     fn drop(&mut self) {
         if !self.dev.is_null() {
@@ -83,6 +356,13 @@ impl Drop for DecklinkDeviceStatus {
     }
 }
 
+impl Clone for DecklinkDeviceStatus {
+    fn clone(&self) -> Self {
+        unsafe { sdk::cdecklink_status_add_ref(self.dev) };
+        DecklinkDeviceStatus { dev: self.dev }
+    }
+}
+
 fn into_enum<T>(res: Result<i64, SdkError>) -> Result<T, SdkError>
 where
     T: FromPrimitive,
@@ -94,24 +374,44 @@ where
 }
 
 impl DecklinkDeviceStatus {
+    /// Wrap a raw pointer. This takes ownership of the reference returned by the query
     pub(crate) fn from(ptr: *mut sdk::cdecklink_status_t) -> DecklinkDeviceStatus {
         DecklinkDeviceStatus { dev: ptr }
     }
 
-    // TODO - do separate like attributes
-    fn get_int(&self, id: u32) -> Result<i64, SdkError> {
+    /// Read an integer status value by its raw id.
+    /// This allows reading values that are not yet wrapped by this crate.
+    pub fn get_int(&self, id: u32) -> Result<i64, SdkError> {
         let mut value = 0;
         let result = unsafe { sdk::cdecklink_status_get_int(self.dev, id, &mut value) };
         SdkError::result_or(result, value)
     }
 
-    fn get_bool(&self, id: u32) -> Result<bool, SdkError> {
+    /// Read a boolean status value by its raw id.
+    pub fn get_bool(&self, id: u32) -> Result<bool, SdkError> {
         let mut value = false;
         let result = unsafe { sdk::cdecklink_status_get_flag(self.dev, id, &mut value) };
         SdkError::result_or(result, value)
     }
 
-    fn get_bytes(&self, id: u32) -> Result<Vec<u8>, SdkError> {
+    /// Read a floating point status value by its raw id.
+    pub fn get_float(&self, id: u32) -> Result<f64, SdkError> {
+        let mut value = 0.0;
+        let result = unsafe { sdk::cdecklink_status_get_float(self.dev, id, &mut value) };
+        SdkError::result_or(result, value)
+    }
+
+    /// Read a string status value by its raw id.
+    pub fn get_string(&self, id: u32) -> Result<String, SdkError> {
+        unsafe {
+            let mut value = null();
+            let result = sdk::cdecklink_status_get_string(self.dev, id, &mut value);
+            SdkError::result_or_else(result, || convert_and_release_c_string(value))
+        }
+    }
+
+    /// Read a byte array status value by its raw id.
+    pub fn get_bytes(&self, id: u32) -> Result<Vec<u8>, SdkError> {
         let mut byte_count = 0;
         let result =
             unsafe { sdk::cdecklink_status_get_bytes(self.dev, id, null_mut(), &mut byte_count) };
@@ -133,7 +433,8 @@ impl DecklinkDeviceStatus {
 
     /// The detected video input mode (BMDDisplayMode), available on devices which support input format detection.
     pub fn detected_video_input_mode(&self) -> Result<DecklinkDisplayModeId, SdkError> {
-        into_enum(self.get_int(sdk::_DecklinkStatusID_decklinkStatusDetectedVideoInputMode))
+        self.get_int(sdk::_DecklinkStatusID_decklinkStatusDetectedVideoInputMode)
+            .map(|v| DecklinkDisplayModeId::from(v as u32))
     }
     pub fn detected_video_input_flags(&self) -> Result<DecklinkVideoStatusFlags, SdkError> {
         self.get_int(sdk::_DecklinkStatusID_decklinkStatusDetectedVideoInputFlags)
@@ -141,11 +442,13 @@ impl DecklinkDeviceStatus {
     }
     /// The current video input mode (BMDDisplayMode).
     pub fn current_video_input_mode(&self) -> Result<DecklinkDisplayModeId, SdkError> {
-        into_enum(self.get_int(sdk::_DecklinkStatusID_decklinkStatusCurrentVideoInputMode))
+        self.get_int(sdk::_DecklinkStatusID_decklinkStatusCurrentVideoInputMode)
+            .map(|v| DecklinkDisplayModeId::from(v as u32))
     }
     /// The current video input pixel format (BMDPixelFormat).
     pub fn current_video_input_pixel_format(&self) -> Result<DecklinkPixelFormat, SdkError> {
-        into_enum(self.get_int(sdk::_DecklinkStatusID_decklinkStatusCurrentVideoInputPixelFormat))
+        self.get_int(sdk::_DecklinkStatusID_decklinkStatusCurrentVideoInputPixelFormat)
+            .map(|v| DecklinkPixelFormat::from(v as u32))
     }
     /// The current video input flags (BMDDeckLinkVideoStatusFlags)
     pub fn current_video_input_flags(&self) -> Result<DecklinkVideoStatusFlags, SdkError> {
@@ -154,7 +457,8 @@ impl DecklinkDeviceStatus {
     }
     /// The current video output mode (BMDDisplayMode).
     pub fn current_video_output_mode(&self) -> Result<DecklinkDisplayModeId, SdkError> {
-        into_enum(self.get_int(sdk::_DecklinkStatusID_decklinkStatusCurrentVideoOutputMode))
+        self.get_int(sdk::_DecklinkStatusID_decklinkStatusCurrentVideoOutputMode)
+            .map(|v| DecklinkDisplayModeId::from(v as u32))
     }
     /// The current video output flags (BMDDeckLinkVideoStatusFlags).
     pub fn current_video_output_flags(&self) -> Result<DecklinkVideoStatusFlags, SdkError> {
@@ -173,25 +477,31 @@ impl DecklinkDeviceStatus {
     }
     /// The last video output pixel format (BMDPixelFormat).
     pub fn last_video_output_pixel_format(&self) -> Result<DecklinkPixelFormat, SdkError> {
-        into_enum(self.get_int(sdk::_DecklinkStatusID_decklinkStatusLastVideoOutputPixelFormat))
+        self.get_int(sdk::_DecklinkStatusID_decklinkStatusLastVideoOutputPixelFormat)
+            .map(|v| DecklinkPixelFormat::from(v as u32))
     }
     /// The detected reference input mode (BMDDisplayMode), available on devices which support reference input format detection.
     pub fn reference_signal_mode(&self) -> Result<DecklinkDisplayModeId, SdkError> {
-        into_enum(self.get_int(sdk::_DecklinkStatusID_decklinkStatusReferenceSignalMode))
+        self.get_int(sdk::_DecklinkStatusID_decklinkStatusReferenceSignalMode)
+            .map(|v| DecklinkDisplayModeId::from(v as u32))
     }
-    pub fn reference_signal_flags(&self) -> Result<i64, SdkError> {
+    /// The detected reference input flags (BMDDeckLinkVideoStatusFlags), available on devices which support reference input format detection.
+    pub fn reference_signal_flags(&self) -> Result<DecklinkVideoStatusFlags, SdkError> {
         self.get_int(sdk::_DecklinkStatusID_decklinkStatusReferenceSignalFlags)
+            .map(|v| DecklinkVideoStatusFlags::from_bits_retain(v as u32))
     }
     pub fn duplex_mode(&self) -> Result<DecklinkDuplexStatus, SdkError> {
         into_enum(self.get_int(sdk::_DecklinkStatusID_decklinkStatusDuplexMode))
     }
     /// The current busy state of the device. (See BMDDeviceBusyState for more information).
-    pub fn busy(&self) -> Result<i64, SdkError> {
+    pub fn busy(&self) -> Result<DecklinkDeviceBusyState, SdkError> {
         self.get_int(sdk::_DecklinkStatusID_decklinkStatusBusy)
+            .map(|v| DecklinkDeviceBusyState::from_bits_retain(v as u32))
     }
     /// The interchangeable panel installed (BMDPanelType).
-    pub fn interchangeable_panel_type(&self) -> Result<i64, SdkError> {
+    pub fn interchangeable_panel_type(&self) -> Result<DecklinkPanelType, SdkError> {
         self.get_int(sdk::_DecklinkStatusID_decklinkStatusInterchangeablePanelType)
+            .map(|v| DecklinkPanelType::from(v as u32))
     }
 
     /// True if the video input signal is locked.
@@ -204,7 +514,244 @@ impl DecklinkDeviceStatus {
     }
 
     /// The received EDID of a connected HDMI sink device.
+    /// This can be parsed with `decklink::edid::Edid::parse`.
     pub fn received_edid(&self) -> Result<Vec<u8>, SdkError> {
         self.get_bytes(sdk::_DecklinkStatusID_decklinkStatusReceivedEDID)
     }
+
+    /// Read the current value of a status.
+    /// This is convenient for re-reading a value after being notified of a change.
+    pub fn get(&self, id: DecklinkStatusId) -> Result<DecklinkStatusValue, SdkError> {
+        match id {
+            DecklinkStatusId::DetectedVideoInputMode => self
+                .detected_video_input_mode()
+                .map(DecklinkStatusValue::DisplayMode),
+            DecklinkStatusId::DetectedVideoInputFlags => self
+                .detected_video_input_flags()
+                .map(DecklinkStatusValue::VideoStatusFlags),
+            DecklinkStatusId::CurrentVideoInputMode => self
+                .current_video_input_mode()
+                .map(DecklinkStatusValue::DisplayMode),
+            DecklinkStatusId::CurrentVideoInputPixelFormat => self
+                .current_video_input_pixel_format()
+                .map(DecklinkStatusValue::PixelFormat),
+            DecklinkStatusId::CurrentVideoInputFlags => self
+                .current_video_input_flags()
+                .map(DecklinkStatusValue::VideoStatusFlags),
+            DecklinkStatusId::CurrentVideoOutputMode => self
+                .current_video_output_mode()
+                .map(DecklinkStatusValue::DisplayMode),
+            DecklinkStatusId::CurrentVideoOutputFlags => self
+                .current_video_output_flags()
+                .map(DecklinkStatusValue::VideoStatusFlags),
+            DecklinkStatusId::PCIExpressLinkWidth => self
+                .pci_express_link_width()
+                .map(|v| DecklinkStatusValue::Int(v as i64)),
+            DecklinkStatusId::PCIExpressLinkSpeed => self
+                .pci_express_link_speed()
+                .map(|v| DecklinkStatusValue::Int(v as i64)),
+            DecklinkStatusId::LastVideoOutputPixelFormat => self
+                .last_video_output_pixel_format()
+                .map(DecklinkStatusValue::PixelFormat),
+            DecklinkStatusId::ReferenceSignalMode => self
+                .reference_signal_mode()
+                .map(DecklinkStatusValue::DisplayMode),
+            DecklinkStatusId::ReferenceSignalFlags => self
+                .reference_signal_flags()
+                .map(DecklinkStatusValue::VideoStatusFlags),
+            DecklinkStatusId::DuplexMode => {
+                self.duplex_mode().map(DecklinkStatusValue::DuplexStatus)
+            }
+            DecklinkStatusId::Busy => self.busy().map(DecklinkStatusValue::BusyState),
+            DecklinkStatusId::InterchangeablePanelType => self
+                .interchangeable_panel_type()
+                .map(DecklinkStatusValue::PanelType),
+            DecklinkStatusId::VideoInputSignalLocked => self
+                .video_input_signal_locked()
+                .map(DecklinkStatusValue::Flag),
+            DecklinkStatusId::ReferenceSignalLocked => self
+                .reference_signal_locked()
+                .map(DecklinkStatusValue::Flag),
+            DecklinkStatusId::ReceivedEDID => self.received_edid().map(DecklinkStatusValue::Bytes),
+            // The type of an unknown status is not known, so it must be read with one of the raw getters
+            DecklinkStatusId::Other(_) => Err(SdkError::NOTIMPL),
+        }
+    }
+
+    /// Read every status value of the device.
+    /// Values which are not supported by the device in its current state are `None`.
+    pub fn snapshot(&self) -> StatusSnapshot {
+        StatusSnapshot {
+            detected_video_input_mode: self.detected_video_input_mode().ok(),
+            detected_video_input_flags: self.detected_video_input_flags().ok(),
+            current_video_input_mode: self.current_video_input_mode().ok(),
+            current_video_input_pixel_format: self.current_video_input_pixel_format().ok(),
+            current_video_input_flags: self.current_video_input_flags().ok(),
+            current_video_output_mode: self.current_video_output_mode().ok(),
+            current_video_output_flags: self.current_video_output_flags().ok(),
+            pci_express_link_width: self.pci_express_link_width().ok(),
+            pci_express_link_speed: self.pci_express_link_speed().ok(),
+            last_video_output_pixel_format: self.last_video_output_pixel_format().ok(),
+            reference_signal_mode: self.reference_signal_mode().ok(),
+            reference_signal_flags: self.reference_signal_flags().ok(),
+            duplex_mode: self.duplex_mode().ok(),
+            busy: self.busy().ok(),
+            interchangeable_panel_type: self.interchangeable_panel_type().ok(),
+            video_input_signal_locked: self.video_input_signal_locked().ok(),
+            reference_signal_locked: self.reference_signal_locked().ok(),
+            received_edid: self.received_edid().ok(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_id_round_trips() {
+        let mut values = Vec::new();
+        for id in DecklinkStatusId::iter() {
+            let value = u64::from(id);
+            assert_eq!(DecklinkStatusId::from(value), id, "{:?}", id);
+            assert!(!values.contains(&value), "{:?} is not unique", id);
+            values.push(value);
+        }
+
+        // Including values which do not fit in the sdk's 32bit ids
+        for unknown in [u64::from(u32::from_be_bytes(*b"zzzz")), 1 << 40] {
+            assert_eq!(
+                DecklinkStatusId::from(unknown),
+                DecklinkStatusId::Other(unknown)
+            );
+            assert_eq!(u64::from(DecklinkStatusId::Other(unknown)), unknown);
+        }
+    }
+
+    #[test]
+    fn panel_type_round_trips() {
+        for panel in [
+            DecklinkPanelType::NotDetected,
+            DecklinkPanelType::TeranexMiniSmartPanel,
+        ] {
+            assert_eq!(DecklinkPanelType::from(u32::from(panel)), panel);
+        }
+
+        let unknown = u32::from_be_bytes(*b"zzzz");
+        assert_eq!(
+            DecklinkPanelType::from(unknown),
+            DecklinkPanelType::Other(unknown)
+        );
+        assert_eq!(u32::from(DecklinkPanelType::Other(unknown)), unknown);
+    }
+
+    fn locked_snapshot() -> StatusSnapshot {
+        StatusSnapshot {
+            detected_video_input_mode: Some(DecklinkDisplayModeId::HD1080i50),
+            current_video_input_pixel_format: Some(DecklinkPixelFormat::Format10BitYUV),
+            pci_express_link_width: Some(4),
+            duplex_mode: Some(DecklinkDuplexStatus::FullDuplex),
+            busy: Some(DecklinkDeviceBusyState::CAPTURE),
+            video_input_signal_locked: Some(true),
+            received_edid: Some(vec![0x00, 0xff, 0xff, 0x00]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn get_typed_values() {
+        let snapshot = locked_snapshot();
+
+        assert_eq!(
+            snapshot.get(DecklinkStatusId::DetectedVideoInputMode),
+            Some(DecklinkStatusValue::DisplayMode(
+                DecklinkDisplayModeId::HD1080i50
+            ))
+        );
+        assert_eq!(
+            snapshot.get(DecklinkStatusId::PCIExpressLinkWidth),
+            Some(DecklinkStatusValue::Int(4))
+        );
+        assert_eq!(
+            snapshot.get(DecklinkStatusId::Busy),
+            Some(DecklinkStatusValue::BusyState(
+                DecklinkDeviceBusyState::CAPTURE
+            ))
+        );
+        assert_eq!(
+            snapshot.get(DecklinkStatusId::VideoInputSignalLocked),
+            Some(DecklinkStatusValue::Flag(true))
+        );
+        assert_eq!(
+            snapshot.get(DecklinkStatusId::ReceivedEDID),
+            Some(DecklinkStatusValue::Bytes(vec![0x00, 0xff, 0xff, 0x00]))
+        );
+        assert_eq!(snapshot.get(DecklinkStatusId::ReferenceSignalMode), None);
+        // The type of an unknown status is not known, so it is never part of a snapshot
+        assert_eq!(snapshot.get(DecklinkStatusId::Other(0x12345678)), None);
+    }
+
+    #[test]
+    fn diff_of_equal_snapshots_is_empty() {
+        assert!(locked_snapshot().diff(&locked_snapshot()).is_empty());
+        assert!(StatusSnapshot::default()
+            .diff(&StatusSnapshot::default())
+            .is_empty());
+    }
+
+    #[test]
+    fn diff_finds_added_removed_and_changed_values() {
+        let before = locked_snapshot();
+        let after = StatusSnapshot {
+            // Changed
+            detected_video_input_mode: Some(DecklinkDisplayModeId::HD1080p25),
+            video_input_signal_locked: Some(false),
+            // Removed
+            current_video_input_pixel_format: None,
+            // Added
+            reference_signal_locked: Some(true),
+            ..locked_snapshot()
+        };
+
+        let expected = vec![
+            DecklinkStatusId::DetectedVideoInputMode,
+            DecklinkStatusId::CurrentVideoInputPixelFormat,
+            DecklinkStatusId::VideoInputSignalLocked,
+            DecklinkStatusId::ReferenceSignalLocked,
+        ];
+        assert_eq!(before.diff(&after), expected);
+        // The same statuses differ in either direction
+        assert_eq!(after.diff(&before), expected);
+    }
+
+    #[test]
+    fn diff_compares_bytes() {
+        let before = locked_snapshot();
+        let mut after = locked_snapshot();
+
+        // Same length, different content
+        after.received_edid = Some(vec![0x00, 0xff, 0xff, 0x01]);
+        assert_eq!(before.diff(&after), [DecklinkStatusId::ReceivedEDID]);
+
+        // Different length
+        after.received_edid = Some(vec![0x00, 0xff, 0xff]);
+        assert_eq!(before.diff(&after), [DecklinkStatusId::ReceivedEDID]);
+
+        // Empty is not the same as unsupported
+        after.received_edid = Some(Vec::new());
+        let mut removed = locked_snapshot();
+        removed.received_edid = None;
+        assert_eq!(after.diff(&removed), [DecklinkStatusId::ReceivedEDID]);
+    }
+
+    #[test]
+    fn diff_ignores_unknown_statuses() {
+        // Unknown statuses can't be held by a snapshot, so are never reported as changed
+        let before = StatusSnapshot::default();
+        let after = locked_snapshot();
+        assert!(!before
+            .diff(&after)
+            .iter()
+            .any(|id| matches!(id, DecklinkStatusId::Other(_))));
+    }
 }
@@ -0,0 +1,393 @@
+//! The order that the sdk calls are made in when enabling and tearing down input and output handles.
+//! The calls are behind small traits, so that the sequencing can be checked without any hardware.
+
+use crate::device::output::DecklinkVideoOutputFlags;
+use crate::display_mode::DecklinkDisplayModeId;
+use crate::SdkError;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// The sdk calls used to enable and disable the video of an output
+pub(crate) trait OutputVideoOps {
+    /// The completion callback, once registered with the output
+    type Callback;
+
+    fn register_callback(&self) -> Result<Self::Callback, SdkError>;
+    /// Remove the callback from the output and free it. It must not be running, or able to run again.
+    fn unregister_callback(&self, callback: Self::Callback);
+    fn enable_video(
+        &self,
+        mode: DecklinkDisplayModeId,
+        flags: DecklinkVideoOutputFlags,
+    ) -> Result<(), SdkError>;
+    fn stop_playback(&self, timescale: i64);
+    /// This blocks until all frame callbacks are complete
+    fn disable_video(&self);
+}
+
+/// Enable the video of an output, claiming `active` for the handle that will own it.
+/// When `with_callback` is set, the callback is registered before enabling so that no completions are missed.
+pub(crate) fn enable_output_video<O: OutputVideoOps>(
+    ops: &O,
+    active: &AtomicBool,
+    mode: DecklinkDisplayModeId,
+    flags: DecklinkVideoOutputFlags,
+    with_callback: bool,
+) -> Result<Option<O::Callback>, SdkError> {
+    // Registering the callback would replace the one belonging to the video that is already enabled
+    if active.swap(true, Ordering::Relaxed) {
+        // TODO - better mode
+        return Err(SdkError::ACCESSDENIED);
+    }
+
+    let callback = if with_callback {
+        match ops.register_callback() {
+            Ok(callback) => Some(callback),
+            Err(e) => {
+                active.store(false, Ordering::Relaxed);
+                return Err(e);
+            }
+        }
+    } else {
+        None
+    };
+
+    match ops.enable_video(mode, flags) {
+        Ok(()) => Ok(callback),
+        Err(e) => {
+            // The output was never enabled, so the callback cannot have been invoked
+            if let Some(callback) = callback {
+                ops.unregister_callback(callback);
+            }
+            active.store(false, Ordering::Relaxed);
+            Err(e)
+        }
+    }
+}
+
+/// Tear down the video of an output: stop playback, disable the output, then remove and free the callback
+pub(crate) fn teardown_output_video<O: OutputVideoOps>(
+    ops: &O,
+    active: &AtomicBool,
+    running_timescale: Option<i64>,
+    callback: Option<O::Callback>,
+) {
+    if let Some(timescale) = running_timescale {
+        ops.stop_playback(timescale);
+    }
+
+    ops.disable_video();
+    active.store(false, Ordering::Relaxed);
+
+    if let Some(callback) = callback {
+        ops.unregister_callback(callback);
+    }
+}
+
+/// The sdk calls used to disable the audio of an output
+pub(crate) trait OutputAudioOps {
+    fn disable_audio(&self);
+}
+
+/// Tear down the audio of an output.
+/// No audio callback is registered, so this can be done independently of the video.
+pub(crate) fn teardown_output_audio<O: OutputAudioOps>(ops: &O, active: &AtomicBool) {
+    ops.disable_audio();
+    active.store(false, Ordering::Relaxed);
+}
+
+/// The sdk calls used to tear down an input
+pub(crate) trait InputOps {
+    /// The frame callback, once registered with the input
+    type Callback;
+
+    fn stop_streams(&self);
+    fn disable_video(&self);
+    fn disable_audio(&self);
+    /// Remove the callback from the input and free it. It must not be running, or able to run again.
+    fn unregister_callback(&self, callback: Self::Callback);
+}
+
+/// What an input handle has enabled, which is all that it tears down
+#[derive(Debug, Default, Copy, Clone)]
+pub(crate) struct InputEnabled {
+    pub video: bool,
+    pub audio: bool,
+    pub streams: bool,
+}
+
+/// Tear down an input: stop the streams, disable the video and audio, then remove and free the callback.
+/// Only what this handle enabled is disabled, so that another handle to the same input is left capturing.
+pub(crate) fn teardown_input<I: InputOps>(
+    ops: &I,
+    enabled: InputEnabled,
+    callback: Option<I::Callback>,
+) {
+    // The callback must not be freed while the streams are still able to call it
+    if enabled.streams || callback.is_some() {
+        ops.stop_streams();
+    }
+    if enabled.video {
+        ops.disable_video();
+    }
+    if enabled.audio {
+        ops.disable_audio();
+    }
+
+    if let Some(callback) = callback {
+        ops.unregister_callback(callback);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    /// Records the calls made, and fails the ones it is told to
+    #[derive(Default)]
+    struct MockOps {
+        calls: RefCell<Vec<&'static str>>,
+        fail_register: bool,
+        fail_enable: bool,
+    }
+
+    impl MockOps {
+        fn record(&self, call: &'static str) {
+            self.calls.borrow_mut().push(call);
+        }
+        fn calls(&self) -> Vec<&'static str> {
+            self.calls.borrow().clone()
+        }
+    }
+
+    impl OutputVideoOps for MockOps {
+        type Callback = ();
+
+        fn register_callback(&self) -> Result<(), SdkError> {
+            self.record("register_callback");
+            if self.fail_register {
+                Err(SdkError::OUTOFMEMORY)
+            } else {
+                Ok(())
+            }
+        }
+        fn unregister_callback(&self, _callback: ()) {
+            self.record("unregister_callback");
+        }
+        fn enable_video(
+            &self,
+            _mode: DecklinkDisplayModeId,
+            _flags: DecklinkVideoOutputFlags,
+        ) -> Result<(), SdkError> {
+            self.record("enable_video");
+            if self.fail_enable {
+                Err(SdkError::FAIL)
+            } else {
+                Ok(())
+            }
+        }
+        fn stop_playback(&self, _timescale: i64) {
+            self.record("stop_playback");
+        }
+        fn disable_video(&self) {
+            self.record("disable_video");
+        }
+    }
+
+    impl OutputAudioOps for MockOps {
+        fn disable_audio(&self) {
+            self.record("disable_audio");
+        }
+    }
+
+    impl InputOps for MockOps {
+        type Callback = ();
+
+        fn stop_streams(&self) {
+            self.record("stop_streams");
+        }
+        fn disable_video(&self) {
+            self.record("disable_video");
+        }
+        fn disable_audio(&self) {
+            self.record("disable_audio");
+        }
+        fn unregister_callback(&self, _callback: ()) {
+            self.record("unregister_callback");
+        }
+    }
+
+    fn enable(ops: &MockOps, active: &AtomicBool, with_callback: bool) -> Result<bool, SdkError> {
+        enable_output_video(
+            ops,
+            active,
+            DecklinkDisplayModeId::HD1080i50,
+            DecklinkVideoOutputFlags::empty(),
+            with_callback,
+        )
+        .map(|callback| callback.is_some())
+    }
+
+    #[test]
+    fn enable_scheduled_registers_callback_first() {
+        let ops = MockOps::default();
+        let active = AtomicBool::new(false);
+
+        assert!(matches!(enable(&ops, &active, true), Ok(true)));
+        assert_eq!(ops.calls(), vec!["register_callback", "enable_video"]);
+        assert!(active.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn enable_sync_has_no_callback() {
+        let ops = MockOps::default();
+        let active = AtomicBool::new(false);
+
+        assert!(matches!(enable(&ops, &active, false), Ok(false)));
+        assert_eq!(ops.calls(), vec!["enable_video"]);
+    }
+
+    #[test]
+    fn enable_when_active_is_refused() {
+        let ops = MockOps::default();
+        let active = AtomicBool::new(true);
+
+        assert!(matches!(
+            enable(&ops, &active, true),
+            Err(SdkError::ACCESSDENIED)
+        ));
+        assert!(ops.calls().is_empty());
+        // The existing video still owns the output
+        assert!(active.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn enable_failure_unregisters_callback() {
+        let ops = MockOps {
+            fail_enable: true,
+            ..Default::default()
+        };
+        let active = AtomicBool::new(false);
+
+        assert!(matches!(enable(&ops, &active, true), Err(SdkError::FAIL)));
+        assert_eq!(
+            ops.calls(),
+            vec!["register_callback", "enable_video", "unregister_callback"]
+        );
+        assert!(!active.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn register_failure_does_not_enable() {
+        let ops = MockOps {
+            fail_register: true,
+            ..Default::default()
+        };
+        let active = AtomicBool::new(false);
+
+        assert!(matches!(
+            enable(&ops, &active, true),
+            Err(SdkError::OUTOFMEMORY)
+        ));
+        assert_eq!(ops.calls(), vec!["register_callback"]);
+        assert!(!active.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn output_video_teardown_order() {
+        let ops = MockOps::default();
+        let active = AtomicBool::new(true);
+
+        teardown_output_video(&ops, &active, Some(25000), Some(()));
+        assert_eq!(
+            ops.calls(),
+            vec!["stop_playback", "disable_video", "unregister_callback"]
+        );
+        assert!(!active.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn output_video_teardown_when_stopped() {
+        let ops = MockOps::default();
+        let active = AtomicBool::new(true);
+
+        teardown_output_video(&ops, &active, None, None);
+        assert_eq!(ops.calls(), vec!["disable_video"]);
+        assert!(!active.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn output_audio_teardown() {
+        let ops = MockOps::default();
+        let active = AtomicBool::new(true);
+
+        teardown_output_audio(&ops, &active);
+        assert_eq!(ops.calls(), vec!["disable_audio"]);
+        assert!(!active.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn output_teardown_video_before_audio() {
+        // As done by `PrerollingOutput::disable`, so that no frame callbacks are running when the audio goes
+        let ops = MockOps::default();
+        let video_active = AtomicBool::new(true);
+        let audio_active = AtomicBool::new(true);
+
+        teardown_output_video(&ops, &video_active, Some(25000), Some(()));
+        teardown_output_audio(&ops, &audio_active);
+        assert_eq!(
+            ops.calls(),
+            vec![
+                "stop_playback",
+                "disable_video",
+                "unregister_callback",
+                "disable_audio"
+            ]
+        );
+    }
+
+    #[test]
+    fn input_teardown_order() {
+        let ops = MockOps::default();
+        let enabled = InputEnabled {
+            video: true,
+            audio: true,
+            streams: true,
+        };
+
+        teardown_input(&ops, enabled, Some(()));
+        assert_eq!(
+            ops.calls(),
+            vec![
+                "stop_streams",
+                "disable_video",
+                "disable_audio",
+                "unregister_callback"
+            ]
+        );
+    }
+
+    #[test]
+    fn input_teardown_leaves_others_alone() {
+        // A handle used only to probe the input must not disturb another capture
+        let ops = MockOps::default();
+        teardown_input(&ops, InputEnabled::default(), None);
+        assert!(ops.calls().is_empty());
+
+        let ops = MockOps::default();
+        let enabled = InputEnabled {
+            video: true,
+            ..Default::default()
+        };
+        teardown_input(&ops, enabled, None);
+        assert_eq!(ops.calls(), vec!["disable_video"]);
+    }
+
+    #[test]
+    fn input_teardown_stops_streams_before_freeing_callback() {
+        // The streams were started by another handle, but are delivering frames to this callback
+        let ops = MockOps::default();
+        teardown_input(&ops, InputEnabled::default(), Some(()));
+        assert_eq!(ops.calls(), vec!["stop_streams", "unregister_callback"]);
+    }
+}
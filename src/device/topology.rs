@@ -0,0 +1,297 @@
+use crate::device::{get_devices, DecklinkDevice};
+use crate::SdkError;
+
+/// A device as part of a `DeviceTopology`, along with the identifiers used to place it
+pub struct TopologyDevice<D = DecklinkDevice> {
+    pub device: D,
+
+    /// A unique identifier for the device, which is stable across reboots
+    pub persistent_id: Option<i64>,
+    /// An identifier based on the physical location of the device, such as the slot it is installed in
+    pub topological_id: Option<i64>,
+    /// The index of this sub-device on its card
+    pub sub_device_index: Option<i64>,
+    /// The persistent id of the device this is paired with, such as the other half of a full-duplex connector
+    pub paired_device_persistent_id: Option<i64>,
+}
+
+/// A physical card, with the sub-devices it contains
+pub struct PhysicalCard<D = DecklinkDevice> {
+    /// The shared group id of the sub-devices, if the card reports one
+    pub device_group_id: Option<i64>,
+    /// The sub-devices of the card, ordered by their sub-device index
+    pub sub_devices: Vec<TopologyDevice<D>>,
+}
+
+impl<D> PhysicalCard<D> {
+    /// Get the sub-device at `index` on this card
+    pub fn sub_device(&self, index: i64) -> Option<&TopologyDevice<D>> {
+        self.sub_devices
+            .iter()
+            .find(|d| d.sub_device_index == Some(index))
+    }
+
+    fn sort_key(&self) -> (Option<i64>, Option<i64>) {
+        let topological_id = self
+            .sub_devices
+            .iter()
+            .filter_map(|d| d.topological_id)
+            .min();
+        (topological_id, self.device_group_id)
+    }
+}
+
+/// The devices in the system, grouped by the physical card they belong to.
+/// Cards are ordered by their topological id, so the order is stable across reboots as long as no cards are moved.
+pub struct DeviceTopology<D = DecklinkDevice> {
+    cards: Vec<PhysicalCard<D>>,
+}
+
+impl DeviceTopology {
+    /// Build the topology of all the devices in the system
+    pub fn discover() -> Result<DeviceTopology, SdkError> {
+        Ok(DeviceTopology::new(get_devices()?))
+    }
+
+    /// Build the topology of a list of devices.
+    /// Devices which do not report a group id are treated as a card of their own.
+    pub fn new(devices: Vec<DecklinkDevice>) -> DeviceTopology {
+        let devices = devices
+            .into_iter()
+            .map(|device| match device.get_attributes() {
+                Ok(attributes) => (
+                    attributes.device_group_id().ok(),
+                    TopologyDevice {
+                        persistent_id: attributes.persistent_id().ok(),
+                        topological_id: attributes.topological_id().ok(),
+                        sub_device_index: attributes.sub_device_index().ok(),
+                        paired_device_persistent_id: attributes.paired_device_persistent_id().ok(),
+                        device,
+                    },
+                ),
+                Err(_) => (
+                    None,
+                    TopologyDevice {
+                        device,
+                        persistent_id: None,
+                        topological_id: None,
+                        sub_device_index: None,
+                        paired_device_persistent_id: None,
+                    },
+                ),
+            })
+            .collect();
+
+        DeviceTopology {
+            cards: group_cards(devices),
+        }
+    }
+}
+
+impl<D> DeviceTopology<D> {
+    /// The cards in the system
+    pub fn cards(&self) -> &[PhysicalCard<D>] {
+        &self.cards
+    }
+
+    /// Get a sub-device by the index of its card in this topology, and its sub-device index on that card
+    pub fn device_at(
+        &self,
+        card_index: usize,
+        sub_device_index: i64,
+    ) -> Option<&TopologyDevice<D>> {
+        self.cards
+            .get(card_index)
+            .and_then(|c| c.sub_device(sub_device_index))
+    }
+
+    /// Iterate over every device, in card order
+    pub fn devices(&self) -> impl Iterator<Item = &TopologyDevice<D>> {
+        self.cards.iter().flat_map(|c| c.sub_devices.iter())
+    }
+
+    /// Find a device by its persistent id
+    pub fn find_by_persistent_id(&self, persistent_id: i64) -> Option<&TopologyDevice<D>> {
+        self.devices()
+            .find(|d| d.persistent_id == Some(persistent_id))
+    }
+
+    /// Find the device paired with `device`, such as the other half of a full-duplex connector
+    pub fn paired_device(&self, device: &TopologyDevice<D>) -> Option<&TopologyDevice<D>> {
+        device
+            .paired_device_persistent_id
+            .and_then(|id| self.find_by_persistent_id(id))
+    }
+}
+
+/// Group devices, each given with its device group id, into cards.
+/// The sub-devices of each card are ordered by their sub-device index, and the cards by their topological id.
+fn group_cards<D>(devices: Vec<(Option<i64>, TopologyDevice<D>)>) -> Vec<PhysicalCard<D>> {
+    let mut cards: Vec<PhysicalCard<D>> = Vec::new();
+
+    for (device_group_id, entry) in devices {
+        let card = match device_group_id {
+            Some(group_id) => cards
+                .iter_mut()
+                .find(|c| c.device_group_id == Some(group_id)),
+            None => None,
+        };
+        match card {
+            Some(card) => card.sub_devices.push(entry),
+            None => cards.push(PhysicalCard {
+                device_group_id,
+                sub_devices: vec![entry],
+            }),
+        }
+    }
+
+    for card in &mut cards {
+        card.sub_devices.sort_by_key(|d| d.sub_device_index);
+    }
+    // Devices without a topological id sort first, and keep their driver order
+    cards.sort_by_key(|c| c.sort_key());
+
+    cards
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A device as `(name, persistent_id, group_id, sub_device_index, topological_id, paired_id)`
+    type Ids = (
+        &'static str,
+        Option<i64>,
+        Option<i64>,
+        Option<i64>,
+        Option<i64>,
+        Option<i64>,
+    );
+
+    fn topology(devices: &[Ids]) -> DeviceTopology<&'static str> {
+        let devices = devices
+            .iter()
+            .map(
+                |&(name, persistent_id, group_id, sub_device_index, topological_id, paired_id)| {
+                    (
+                        group_id,
+                        TopologyDevice {
+                            device: name,
+                            persistent_id,
+                            topological_id,
+                            sub_device_index,
+                            paired_device_persistent_id: paired_id,
+                        },
+                    )
+                },
+            )
+            .collect();
+        DeviceTopology {
+            cards: group_cards(devices),
+        }
+    }
+
+    fn names(topology: &DeviceTopology<&'static str>) -> Vec<Vec<&'static str>> {
+        topology
+            .cards()
+            .iter()
+            .map(|c| c.sub_devices.iter().map(|d| d.device).collect())
+            .collect()
+    }
+
+    #[test]
+    fn orders_shuffled_devices() {
+        // Two 4 connector cards, with card B in the higher slot, listed by the driver in no particular order
+        let topology = topology(&[
+            ("B2", Some(22), Some(2), Some(2), Some(0x200), None),
+            ("A1", Some(11), Some(1), Some(1), Some(0x100), None),
+            ("B0", Some(20), Some(2), Some(0), Some(0x200), None),
+            ("A3", Some(13), Some(1), Some(3), Some(0x100), None),
+            ("B1", Some(21), Some(2), Some(1), Some(0x200), None),
+            ("A0", Some(10), Some(1), Some(0), Some(0x100), None),
+            ("A2", Some(12), Some(1), Some(2), Some(0x100), None),
+            ("B3", Some(23), Some(2), Some(3), Some(0x200), None),
+        ]);
+
+        assert_eq!(
+            names(&topology),
+            [["A0", "A1", "A2", "A3"], ["B0", "B1", "B2", "B3"]]
+        );
+        assert_eq!(topology.cards()[1].device_group_id, Some(2));
+        assert_eq!(topology.device_at(1, 2).map(|d| d.device), Some("B2"));
+        assert_eq!(topology.device_at(0, 3).map(|d| d.device), Some("A3"));
+        assert!(topology.device_at(0, 4).is_none());
+        assert!(topology.device_at(2, 0).is_none());
+        assert_eq!(
+            topology.devices().map(|d| d.device).collect::<Vec<_>>(),
+            ["A0", "A1", "A2", "A3", "B0", "B1", "B2", "B3"]
+        );
+    }
+
+    #[test]
+    fn order_is_independent_of_driver_order() {
+        let devices = [
+            ("A0", Some(10), Some(1), Some(0), Some(0x100), None),
+            ("A1", Some(11), Some(1), Some(1), Some(0x100), None),
+            ("B0", Some(20), Some(2), Some(0), Some(0x200), None),
+        ];
+        let mut reversed = devices;
+        reversed.reverse();
+
+        assert_eq!(names(&topology(&devices)), names(&topology(&reversed)));
+    }
+
+    #[test]
+    fn handles_missing_attributes() {
+        let topology = topology(&[
+            ("slot2", Some(20), Some(2), Some(0), Some(0x200), None),
+            // No group id, so a card of its own
+            ("lone", Some(30), None, None, Some(0x100), None),
+            // No attributes at all, so sorts first in driver order
+            ("unknown1", None, None, None, None, None),
+            ("unknown2", None, None, None, None, None),
+            // In a group, but without a sub-device index, so first on its card
+            ("slot2-unindexed", Some(21), Some(2), None, None, None),
+        ]);
+
+        assert_eq!(
+            names(&topology),
+            vec![
+                vec!["unknown1"],
+                vec!["unknown2"],
+                vec!["lone"],
+                vec!["slot2-unindexed", "slot2"],
+            ]
+        );
+        assert_eq!(topology.cards()[0].device_group_id, None);
+        assert!(topology.cards()[0].sub_device(0).is_none());
+        assert_eq!(topology.device_at(3, 0).map(|d| d.device), Some("slot2"));
+        assert!(topology.find_by_persistent_id(40).is_none());
+    }
+
+    #[test]
+    fn finds_full_duplex_pair() {
+        // A full-duplex pair, where the input and output sub-devices share a connector
+        let topology = topology(&[
+            ("in", Some(10), Some(1), Some(0), Some(0x100), Some(12)),
+            ("spare", Some(11), Some(1), Some(1), Some(0x100), None),
+            ("out", Some(12), Some(1), Some(2), Some(0x100), Some(10)),
+        ]);
+
+        let input = topology.find_by_persistent_id(10).unwrap();
+        let output = topology.paired_device(input).unwrap();
+        assert_eq!(output.device, "out");
+        assert_eq!(topology.paired_device(output).unwrap().device, "in");
+
+        let spare = topology.device_at(0, 1).unwrap();
+        assert_eq!(spare.device, "spare");
+        assert!(topology.paired_device(spare).is_none());
+    }
+
+    #[test]
+    fn ignores_pair_that_is_not_present() {
+        let topology = topology(&[("in", Some(10), Some(1), Some(0), Some(0x100), Some(99))]);
+        let input = topology.device_at(0, 0).unwrap();
+        assert!(topology.paired_device(input).is_none());
+    }
+}
@@ -1,51 +1,360 @@
 use crate::{sdk, util::convert_and_release_c_string, SdkError};
 use num_traits::FromPrimitive;
+use std::fmt;
 use std::ptr::{null, null_mut};
+use std::str::FromStr;
+use strum::IntoEnumIterator;
 
-#[derive(FromPrimitive, PartialEq, Debug, Copy, Clone)]
+// Modes reported by newer versions of the SDK, which are not in the bundled bindings
+const MODE_2K_DCI_2997: u32 = u32::from_be_bytes(*b"2d29");
+const MODE_2K_DCI_30: u32 = u32::from_be_bytes(*b"2d30");
+const MODE_2K_DCI_4795: u32 = u32::from_be_bytes(*b"2d47");
+const MODE_2K_DCI_48: u32 = u32::from_be_bytes(*b"2d48");
+const MODE_2K_DCI_50: u32 = u32::from_be_bytes(*b"2d50");
+const MODE_2K_DCI_5994: u32 = u32::from_be_bytes(*b"2d59");
+const MODE_2K_DCI_60: u32 = u32::from_be_bytes(*b"2d60");
+const MODE_4K_DCI_2997: u32 = u32::from_be_bytes(*b"4d29");
+const MODE_4K_DCI_30: u32 = u32::from_be_bytes(*b"4d30");
+const MODE_4K_DCI_4795: u32 = u32::from_be_bytes(*b"4d47");
+const MODE_4K_DCI_48: u32 = u32::from_be_bytes(*b"4d48");
+const MODE_4K_DCI_50: u32 = u32::from_be_bytes(*b"4d50");
+const MODE_4K_DCI_5994: u32 = u32::from_be_bytes(*b"4d59");
+const MODE_4K_DCI_60: u32 = u32::from_be_bytes(*b"4d60");
+const MODE_4K_DCI_9590: u32 = u32::from_be_bytes(*b"4d95");
+const MODE_4K_DCI_96: u32 = u32::from_be_bytes(*b"4d96");
+const MODE_4K_DCI_100: u32 = u32::from_be_bytes(*b"4d10");
+const MODE_4K_DCI_11988: u32 = u32::from_be_bytes(*b"4d11");
+const MODE_4K_DCI_120: u32 = u32::from_be_bytes(*b"4d12");
+const MODE_8K_2398: u32 = u32::from_be_bytes(*b"8k23");
+const MODE_8K_24: u32 = u32::from_be_bytes(*b"8k24");
+const MODE_8K_25: u32 = u32::from_be_bytes(*b"8k25");
+const MODE_8K_2997: u32 = u32::from_be_bytes(*b"8k29");
+const MODE_8K_30: u32 = u32::from_be_bytes(*b"8k30");
+const MODE_8K_4795: u32 = u32::from_be_bytes(*b"8k47");
+const MODE_8K_48: u32 = u32::from_be_bytes(*b"8k48");
+const MODE_8K_50: u32 = u32::from_be_bytes(*b"8k50");
+const MODE_8K_5994: u32 = u32::from_be_bytes(*b"8k59");
+const MODE_8K_60: u32 = u32::from_be_bytes(*b"8k60");
+const MODE_8K_DCI_2398: u32 = u32::from_be_bytes(*b"8d23");
+const MODE_8K_DCI_24: u32 = u32::from_be_bytes(*b"8d24");
+const MODE_8K_DCI_25: u32 = u32::from_be_bytes(*b"8d25");
+const MODE_8K_DCI_2997: u32 = u32::from_be_bytes(*b"8d29");
+const MODE_8K_DCI_30: u32 = u32::from_be_bytes(*b"8d30");
+const MODE_8K_DCI_4795: u32 = u32::from_be_bytes(*b"8d47");
+const MODE_8K_DCI_48: u32 = u32::from_be_bytes(*b"8d48");
+const MODE_8K_DCI_50: u32 = u32::from_be_bytes(*b"8d50");
+const MODE_8K_DCI_5994: u32 = u32::from_be_bytes(*b"8d59");
+const MODE_8K_DCI_60: u32 = u32::from_be_bytes(*b"8d60");
+const MODE_1080P_9590: u32 = u32::from_be_bytes(*b"Hp95");
+const MODE_1080P_96: u32 = u32::from_be_bytes(*b"Hp96");
+const MODE_1080P_100: u32 = u32::from_be_bytes(*b"Hp10");
+const MODE_1080P_11988: u32 = u32::from_be_bytes(*b"Hp11");
+const MODE_1080P_120: u32 = u32::from_be_bytes(*b"Hp12");
+const MODE_4K_9590: u32 = u32::from_be_bytes(*b"4k95");
+const MODE_4K_96: u32 = u32::from_be_bytes(*b"4k96");
+const MODE_4K_100: u32 = u32::from_be_bytes(*b"4k10");
+const MODE_4K_11988: u32 = u32::from_be_bytes(*b"4k11");
+const MODE_4K_120: u32 = u32::from_be_bytes(*b"4k12");
+
+#[derive(EnumIter, PartialEq, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DecklinkDisplayModeId {
-    NTSC = sdk::_DecklinkDisplayMode_decklinkModeNTSC as isize,
-    NTSC2398 = sdk::_DecklinkDisplayMode_decklinkModeNTSC2398 as isize,
-    PAL = sdk::_DecklinkDisplayMode_decklinkModePAL as isize,
-    NTSCp = sdk::_DecklinkDisplayMode_decklinkModeNTSCp as isize,
-    PALp = sdk::_DecklinkDisplayMode_decklinkModePALp as isize,
-    HD1080p2398 = sdk::_DecklinkDisplayMode_decklinkModeHD1080p2398 as isize,
-    HD1080p24 = sdk::_DecklinkDisplayMode_decklinkModeHD1080p24 as isize,
-    HD1080p25 = sdk::_DecklinkDisplayMode_decklinkModeHD1080p25 as isize,
-    HD1080p2997 = sdk::_DecklinkDisplayMode_decklinkModeHD1080p2997 as isize,
-    HD1080p30 = sdk::_DecklinkDisplayMode_decklinkModeHD1080p30 as isize,
-    HD1080i50 = sdk::_DecklinkDisplayMode_decklinkModeHD1080i50 as isize,
-    HD1080i5994 = sdk::_DecklinkDisplayMode_decklinkModeHD1080i5994 as isize,
-    HD1080i6000 = sdk::_DecklinkDisplayMode_decklinkModeHD1080i6000 as isize,
-    HD1080p50 = sdk::_DecklinkDisplayMode_decklinkModeHD1080p50 as isize,
-    HD1080p5994 = sdk::_DecklinkDisplayMode_decklinkModeHD1080p5994 as isize,
-    HD1080p6000 = sdk::_DecklinkDisplayMode_decklinkModeHD1080p6000 as isize,
-    HD720p50 = sdk::_DecklinkDisplayMode_decklinkModeHD720p50 as isize,
-    HD720p5994 = sdk::_DecklinkDisplayMode_decklinkModeHD720p5994 as isize,
-    HD720p60 = sdk::_DecklinkDisplayMode_decklinkModeHD720p60 as isize,
-    HD2k2398 = sdk::_DecklinkDisplayMode_decklinkMode2k2398 as isize,
-    HD2k24 = sdk::_DecklinkDisplayMode_decklinkMode2k24 as isize,
-    HD2k25 = sdk::_DecklinkDisplayMode_decklinkMode2k25 as isize,
-    HD2kDCI2398 = sdk::_DecklinkDisplayMode_decklinkMode2kDCI2398 as isize,
-    HD2kDCI24 = sdk::_DecklinkDisplayMode_decklinkMode2kDCI24 as isize,
-    HD2kDCI25 = sdk::_DecklinkDisplayMode_decklinkMode2kDCI25 as isize,
-    UHD4K2160p2398 = sdk::_DecklinkDisplayMode_decklinkMode4K2160p2398 as isize,
-    UHD4K2160p24 = sdk::_DecklinkDisplayMode_decklinkMode4K2160p24 as isize,
-    UHD4K2160p25 = sdk::_DecklinkDisplayMode_decklinkMode4K2160p25 as isize,
-    UHD4K2160p2997 = sdk::_DecklinkDisplayMode_decklinkMode4K2160p2997 as isize,
-    UHD4K2160p30 = sdk::_DecklinkDisplayMode_decklinkMode4K2160p30 as isize,
-    UHD4K2160p50 = sdk::_DecklinkDisplayMode_decklinkMode4K2160p50 as isize,
-    UHD4K2160p5994 = sdk::_DecklinkDisplayMode_decklinkMode4K2160p5994 as isize,
-    UHD4K2160p60 = sdk::_DecklinkDisplayMode_decklinkMode4K2160p60 as isize,
-    UHD4KDCI2398 = sdk::_DecklinkDisplayMode_decklinkMode4kDCI2398 as isize,
-    UHD4KDCI24 = sdk::_DecklinkDisplayMode_decklinkMode4kDCI24 as isize,
-    UHD4KDCI25 = sdk::_DecklinkDisplayMode_decklinkMode4kDCI25 as isize,
-    CintelRAW = sdk::_DecklinkDisplayMode_decklinkModeCintelRAW as isize,
-    CintelCompressedRAW = sdk::_DecklinkDisplayMode_decklinkModeCintelCompressedRAW as isize,
-    Unknown = sdk::_DecklinkDisplayMode_decklinkModeUnknown as isize,
+    NTSC,
+    NTSC2398,
+    PAL,
+    NTSCp,
+    PALp,
+    HD1080p2398,
+    HD1080p24,
+    HD1080p25,
+    HD1080p2997,
+    HD1080p30,
+    HD1080i50,
+    HD1080i5994,
+    HD1080i6000,
+    HD1080p50,
+    HD1080p5994,
+    HD1080p6000,
+    HD720p50,
+    HD720p5994,
+    HD720p60,
+    HD2k2398,
+    HD2k24,
+    HD2k25,
+    HD2kDCI2398,
+    HD2kDCI24,
+    HD2kDCI25,
+    UHD4K2160p2398,
+    UHD4K2160p24,
+    UHD4K2160p25,
+    UHD4K2160p2997,
+    UHD4K2160p30,
+    UHD4K2160p50,
+    UHD4K2160p5994,
+    UHD4K2160p60,
+    UHD4KDCI2398,
+    UHD4KDCI24,
+    UHD4KDCI25,
+    CintelRAW,
+    CintelCompressedRAW,
+    Unknown,
+
+    // Modes from newer versions of the SDK
+    HD2kDCI2997,
+    HD2kDCI30,
+    HD2kDCI4795,
+    HD2kDCI48,
+    HD2kDCI50,
+    HD2kDCI5994,
+    HD2kDCI60,
+    UHD4KDCI2997,
+    UHD4KDCI30,
+    UHD4KDCI4795,
+    UHD4KDCI48,
+    UHD4KDCI50,
+    UHD4KDCI5994,
+    UHD4KDCI60,
+    UHD4KDCI9590,
+    UHD4KDCI96,
+    UHD4KDCI100,
+    UHD4KDCI11988,
+    UHD4KDCI120,
+    UHD8K4320p2398,
+    UHD8K4320p24,
+    UHD8K4320p25,
+    UHD8K4320p2997,
+    UHD8K4320p30,
+    UHD8K4320p4795,
+    UHD8K4320p48,
+    UHD8K4320p50,
+    UHD8K4320p5994,
+    UHD8K4320p60,
+    UHD8KDCI2398,
+    UHD8KDCI24,
+    UHD8KDCI25,
+    UHD8KDCI2997,
+    UHD8KDCI30,
+    UHD8KDCI4795,
+    UHD8KDCI48,
+    UHD8KDCI50,
+    UHD8KDCI5994,
+    UHD8KDCI60,
+    HD1080p9590,
+    HD1080p96,
+    HD1080p100,
+    HD1080p11988,
+    HD1080p120,
+    UHD4K2160p9590,
+    UHD4K2160p96,
+    UHD4K2160p100,
+    UHD4K2160p11988,
+    UHD4K2160p120,
+
+    /// A mode not known to this version of the bindings, as its FourCC value
+    #[strum(disabled)]
+    Other(u32),
+}
+impl From<u32> for DecklinkDisplayModeId {
+    fn from(value: u32) -> Self {
+        match value {
+            sdk::_DecklinkDisplayMode_decklinkModeNTSC => Self::NTSC,
+            sdk::_DecklinkDisplayMode_decklinkModeNTSC2398 => Self::NTSC2398,
+            sdk::_DecklinkDisplayMode_decklinkModePAL => Self::PAL,
+            sdk::_DecklinkDisplayMode_decklinkModeNTSCp => Self::NTSCp,
+            sdk::_DecklinkDisplayMode_decklinkModePALp => Self::PALp,
+            sdk::_DecklinkDisplayMode_decklinkModeHD1080p2398 => Self::HD1080p2398,
+            sdk::_DecklinkDisplayMode_decklinkModeHD1080p24 => Self::HD1080p24,
+            sdk::_DecklinkDisplayMode_decklinkModeHD1080p25 => Self::HD1080p25,
+            sdk::_DecklinkDisplayMode_decklinkModeHD1080p2997 => Self::HD1080p2997,
+            sdk::_DecklinkDisplayMode_decklinkModeHD1080p30 => Self::HD1080p30,
+            sdk::_DecklinkDisplayMode_decklinkModeHD1080i50 => Self::HD1080i50,
+            sdk::_DecklinkDisplayMode_decklinkModeHD1080i5994 => Self::HD1080i5994,
+            sdk::_DecklinkDisplayMode_decklinkModeHD1080i6000 => Self::HD1080i6000,
+            sdk::_DecklinkDisplayMode_decklinkModeHD1080p50 => Self::HD1080p50,
+            sdk::_DecklinkDisplayMode_decklinkModeHD1080p5994 => Self::HD1080p5994,
+            sdk::_DecklinkDisplayMode_decklinkModeHD1080p6000 => Self::HD1080p6000,
+            sdk::_DecklinkDisplayMode_decklinkModeHD720p50 => Self::HD720p50,
+            sdk::_DecklinkDisplayMode_decklinkModeHD720p5994 => Self::HD720p5994,
+            sdk::_DecklinkDisplayMode_decklinkModeHD720p60 => Self::HD720p60,
+            sdk::_DecklinkDisplayMode_decklinkMode2k2398 => Self::HD2k2398,
+            sdk::_DecklinkDisplayMode_decklinkMode2k24 => Self::HD2k24,
+            sdk::_DecklinkDisplayMode_decklinkMode2k25 => Self::HD2k25,
+            sdk::_DecklinkDisplayMode_decklinkMode2kDCI2398 => Self::HD2kDCI2398,
+            sdk::_DecklinkDisplayMode_decklinkMode2kDCI24 => Self::HD2kDCI24,
+            sdk::_DecklinkDisplayMode_decklinkMode2kDCI25 => Self::HD2kDCI25,
+            sdk::_DecklinkDisplayMode_decklinkMode4K2160p2398 => Self::UHD4K2160p2398,
+            sdk::_DecklinkDisplayMode_decklinkMode4K2160p24 => Self::UHD4K2160p24,
+            sdk::_DecklinkDisplayMode_decklinkMode4K2160p25 => Self::UHD4K2160p25,
+            sdk::_DecklinkDisplayMode_decklinkMode4K2160p2997 => Self::UHD4K2160p2997,
+            sdk::_DecklinkDisplayMode_decklinkMode4K2160p30 => Self::UHD4K2160p30,
+            sdk::_DecklinkDisplayMode_decklinkMode4K2160p50 => Self::UHD4K2160p50,
+            sdk::_DecklinkDisplayMode_decklinkMode4K2160p5994 => Self::UHD4K2160p5994,
+            sdk::_DecklinkDisplayMode_decklinkMode4K2160p60 => Self::UHD4K2160p60,
+            sdk::_DecklinkDisplayMode_decklinkMode4kDCI2398 => Self::UHD4KDCI2398,
+            sdk::_DecklinkDisplayMode_decklinkMode4kDCI24 => Self::UHD4KDCI24,
+            sdk::_DecklinkDisplayMode_decklinkMode4kDCI25 => Self::UHD4KDCI25,
+            sdk::_DecklinkDisplayMode_decklinkModeCintelRAW => Self::CintelRAW,
+            sdk::_DecklinkDisplayMode_decklinkModeCintelCompressedRAW => Self::CintelCompressedRAW,
+            sdk::_DecklinkDisplayMode_decklinkModeUnknown => Self::Unknown,
+            MODE_2K_DCI_2997 => Self::HD2kDCI2997,
+            MODE_2K_DCI_30 => Self::HD2kDCI30,
+            MODE_2K_DCI_4795 => Self::HD2kDCI4795,
+            MODE_2K_DCI_48 => Self::HD2kDCI48,
+            MODE_2K_DCI_50 => Self::HD2kDCI50,
+            MODE_2K_DCI_5994 => Self::HD2kDCI5994,
+            MODE_2K_DCI_60 => Self::HD2kDCI60,
+            MODE_4K_DCI_2997 => Self::UHD4KDCI2997,
+            MODE_4K_DCI_30 => Self::UHD4KDCI30,
+            MODE_4K_DCI_4795 => Self::UHD4KDCI4795,
+            MODE_4K_DCI_48 => Self::UHD4KDCI48,
+            MODE_4K_DCI_50 => Self::UHD4KDCI50,
+            MODE_4K_DCI_5994 => Self::UHD4KDCI5994,
+            MODE_4K_DCI_60 => Self::UHD4KDCI60,
+            MODE_4K_DCI_9590 => Self::UHD4KDCI9590,
+            MODE_4K_DCI_96 => Self::UHD4KDCI96,
+            MODE_4K_DCI_100 => Self::UHD4KDCI100,
+            MODE_4K_DCI_11988 => Self::UHD4KDCI11988,
+            MODE_4K_DCI_120 => Self::UHD4KDCI120,
+            MODE_8K_2398 => Self::UHD8K4320p2398,
+            MODE_8K_24 => Self::UHD8K4320p24,
+            MODE_8K_25 => Self::UHD8K4320p25,
+            MODE_8K_2997 => Self::UHD8K4320p2997,
+            MODE_8K_30 => Self::UHD8K4320p30,
+            MODE_8K_4795 => Self::UHD8K4320p4795,
+            MODE_8K_48 => Self::UHD8K4320p48,
+            MODE_8K_50 => Self::UHD8K4320p50,
+            MODE_8K_5994 => Self::UHD8K4320p5994,
+            MODE_8K_60 => Self::UHD8K4320p60,
+            MODE_8K_DCI_2398 => Self::UHD8KDCI2398,
+            MODE_8K_DCI_24 => Self::UHD8KDCI24,
+            MODE_8K_DCI_25 => Self::UHD8KDCI25,
+            MODE_8K_DCI_2997 => Self::UHD8KDCI2997,
+            MODE_8K_DCI_30 => Self::UHD8KDCI30,
+            MODE_8K_DCI_4795 => Self::UHD8KDCI4795,
+            MODE_8K_DCI_48 => Self::UHD8KDCI48,
+            MODE_8K_DCI_50 => Self::UHD8KDCI50,
+            MODE_8K_DCI_5994 => Self::UHD8KDCI5994,
+            MODE_8K_DCI_60 => Self::UHD8KDCI60,
+            MODE_1080P_9590 => Self::HD1080p9590,
+            MODE_1080P_96 => Self::HD1080p96,
+            MODE_1080P_100 => Self::HD1080p100,
+            MODE_1080P_11988 => Self::HD1080p11988,
+            MODE_1080P_120 => Self::HD1080p120,
+            MODE_4K_9590 => Self::UHD4K2160p9590,
+            MODE_4K_96 => Self::UHD4K2160p96,
+            MODE_4K_100 => Self::UHD4K2160p100,
+            MODE_4K_11988 => Self::UHD4K2160p11988,
+            MODE_4K_120 => Self::UHD4K2160p120,
+            v => Self::Other(v),
+        }
+    }
+}
+impl From<DecklinkDisplayModeId> for u32 {
+    fn from(value: DecklinkDisplayModeId) -> Self {
+        match value {
+            DecklinkDisplayModeId::NTSC => sdk::_DecklinkDisplayMode_decklinkModeNTSC,
+            DecklinkDisplayModeId::NTSC2398 => sdk::_DecklinkDisplayMode_decklinkModeNTSC2398,
+            DecklinkDisplayModeId::PAL => sdk::_DecklinkDisplayMode_decklinkModePAL,
+            DecklinkDisplayModeId::NTSCp => sdk::_DecklinkDisplayMode_decklinkModeNTSCp,
+            DecklinkDisplayModeId::PALp => sdk::_DecklinkDisplayMode_decklinkModePALp,
+            DecklinkDisplayModeId::HD1080p2398 => sdk::_DecklinkDisplayMode_decklinkModeHD1080p2398,
+            DecklinkDisplayModeId::HD1080p24 => sdk::_DecklinkDisplayMode_decklinkModeHD1080p24,
+            DecklinkDisplayModeId::HD1080p25 => sdk::_DecklinkDisplayMode_decklinkModeHD1080p25,
+            DecklinkDisplayModeId::HD1080p2997 => sdk::_DecklinkDisplayMode_decklinkModeHD1080p2997,
+            DecklinkDisplayModeId::HD1080p30 => sdk::_DecklinkDisplayMode_decklinkModeHD1080p30,
+            DecklinkDisplayModeId::HD1080i50 => sdk::_DecklinkDisplayMode_decklinkModeHD1080i50,
+            DecklinkDisplayModeId::HD1080i5994 => sdk::_DecklinkDisplayMode_decklinkModeHD1080i5994,
+            DecklinkDisplayModeId::HD1080i6000 => sdk::_DecklinkDisplayMode_decklinkModeHD1080i6000,
+            DecklinkDisplayModeId::HD1080p50 => sdk::_DecklinkDisplayMode_decklinkModeHD1080p50,
+            DecklinkDisplayModeId::HD1080p5994 => sdk::_DecklinkDisplayMode_decklinkModeHD1080p5994,
+            DecklinkDisplayModeId::HD1080p6000 => sdk::_DecklinkDisplayMode_decklinkModeHD1080p6000,
+            DecklinkDisplayModeId::HD720p50 => sdk::_DecklinkDisplayMode_decklinkModeHD720p50,
+            DecklinkDisplayModeId::HD720p5994 => sdk::_DecklinkDisplayMode_decklinkModeHD720p5994,
+            DecklinkDisplayModeId::HD720p60 => sdk::_DecklinkDisplayMode_decklinkModeHD720p60,
+            DecklinkDisplayModeId::HD2k2398 => sdk::_DecklinkDisplayMode_decklinkMode2k2398,
+            DecklinkDisplayModeId::HD2k24 => sdk::_DecklinkDisplayMode_decklinkMode2k24,
+            DecklinkDisplayModeId::HD2k25 => sdk::_DecklinkDisplayMode_decklinkMode2k25,
+            DecklinkDisplayModeId::HD2kDCI2398 => sdk::_DecklinkDisplayMode_decklinkMode2kDCI2398,
+            DecklinkDisplayModeId::HD2kDCI24 => sdk::_DecklinkDisplayMode_decklinkMode2kDCI24,
+            DecklinkDisplayModeId::HD2kDCI25 => sdk::_DecklinkDisplayMode_decklinkMode2kDCI25,
+            DecklinkDisplayModeId::UHD4K2160p2398 => {
+                sdk::_DecklinkDisplayMode_decklinkMode4K2160p2398
+            }
+            DecklinkDisplayModeId::UHD4K2160p24 => sdk::_DecklinkDisplayMode_decklinkMode4K2160p24,
+            DecklinkDisplayModeId::UHD4K2160p25 => sdk::_DecklinkDisplayMode_decklinkMode4K2160p25,
+            DecklinkDisplayModeId::UHD4K2160p2997 => {
+                sdk::_DecklinkDisplayMode_decklinkMode4K2160p2997
+            }
+            DecklinkDisplayModeId::UHD4K2160p30 => sdk::_DecklinkDisplayMode_decklinkMode4K2160p30,
+            DecklinkDisplayModeId::UHD4K2160p50 => sdk::_DecklinkDisplayMode_decklinkMode4K2160p50,
+            DecklinkDisplayModeId::UHD4K2160p5994 => {
+                sdk::_DecklinkDisplayMode_decklinkMode4K2160p5994
+            }
+            DecklinkDisplayModeId::UHD4K2160p60 => sdk::_DecklinkDisplayMode_decklinkMode4K2160p60,
+            DecklinkDisplayModeId::UHD4KDCI2398 => sdk::_DecklinkDisplayMode_decklinkMode4kDCI2398,
+            DecklinkDisplayModeId::UHD4KDCI24 => sdk::_DecklinkDisplayMode_decklinkMode4kDCI24,
+            DecklinkDisplayModeId::UHD4KDCI25 => sdk::_DecklinkDisplayMode_decklinkMode4kDCI25,
+            DecklinkDisplayModeId::CintelRAW => sdk::_DecklinkDisplayMode_decklinkModeCintelRAW,
+            DecklinkDisplayModeId::CintelCompressedRAW => {
+                sdk::_DecklinkDisplayMode_decklinkModeCintelCompressedRAW
+            }
+            DecklinkDisplayModeId::Unknown => sdk::_DecklinkDisplayMode_decklinkModeUnknown,
+            DecklinkDisplayModeId::HD2kDCI2997 => MODE_2K_DCI_2997,
+            DecklinkDisplayModeId::HD2kDCI30 => MODE_2K_DCI_30,
+            DecklinkDisplayModeId::HD2kDCI4795 => MODE_2K_DCI_4795,
+            DecklinkDisplayModeId::HD2kDCI48 => MODE_2K_DCI_48,
+            DecklinkDisplayModeId::HD2kDCI50 => MODE_2K_DCI_50,
+            DecklinkDisplayModeId::HD2kDCI5994 => MODE_2K_DCI_5994,
+            DecklinkDisplayModeId::HD2kDCI60 => MODE_2K_DCI_60,
+            DecklinkDisplayModeId::UHD4KDCI2997 => MODE_4K_DCI_2997,
+            DecklinkDisplayModeId::UHD4KDCI30 => MODE_4K_DCI_30,
+            DecklinkDisplayModeId::UHD4KDCI4795 => MODE_4K_DCI_4795,
+            DecklinkDisplayModeId::UHD4KDCI48 => MODE_4K_DCI_48,
+            DecklinkDisplayModeId::UHD4KDCI50 => MODE_4K_DCI_50,
+            DecklinkDisplayModeId::UHD4KDCI5994 => MODE_4K_DCI_5994,
+            DecklinkDisplayModeId::UHD4KDCI60 => MODE_4K_DCI_60,
+            DecklinkDisplayModeId::UHD4KDCI9590 => MODE_4K_DCI_9590,
+            DecklinkDisplayModeId::UHD4KDCI96 => MODE_4K_DCI_96,
+            DecklinkDisplayModeId::UHD4KDCI100 => MODE_4K_DCI_100,
+            DecklinkDisplayModeId::UHD4KDCI11988 => MODE_4K_DCI_11988,
+            DecklinkDisplayModeId::UHD4KDCI120 => MODE_4K_DCI_120,
+            DecklinkDisplayModeId::UHD8K4320p2398 => MODE_8K_2398,
+            DecklinkDisplayModeId::UHD8K4320p24 => MODE_8K_24,
+            DecklinkDisplayModeId::UHD8K4320p25 => MODE_8K_25,
+            DecklinkDisplayModeId::UHD8K4320p2997 => MODE_8K_2997,
+            DecklinkDisplayModeId::UHD8K4320p30 => MODE_8K_30,
+            DecklinkDisplayModeId::UHD8K4320p4795 => MODE_8K_4795,
+            DecklinkDisplayModeId::UHD8K4320p48 => MODE_8K_48,
+            DecklinkDisplayModeId::UHD8K4320p50 => MODE_8K_50,
+            DecklinkDisplayModeId::UHD8K4320p5994 => MODE_8K_5994,
+            DecklinkDisplayModeId::UHD8K4320p60 => MODE_8K_60,
+            DecklinkDisplayModeId::UHD8KDCI2398 => MODE_8K_DCI_2398,
+            DecklinkDisplayModeId::UHD8KDCI24 => MODE_8K_DCI_24,
+            DecklinkDisplayModeId::UHD8KDCI25 => MODE_8K_DCI_25,
+            DecklinkDisplayModeId::UHD8KDCI2997 => MODE_8K_DCI_2997,
+            DecklinkDisplayModeId::UHD8KDCI30 => MODE_8K_DCI_30,
+            DecklinkDisplayModeId::UHD8KDCI4795 => MODE_8K_DCI_4795,
+            DecklinkDisplayModeId::UHD8KDCI48 => MODE_8K_DCI_48,
+            DecklinkDisplayModeId::UHD8KDCI50 => MODE_8K_DCI_50,
+            DecklinkDisplayModeId::UHD8KDCI5994 => MODE_8K_DCI_5994,
+            DecklinkDisplayModeId::UHD8KDCI60 => MODE_8K_DCI_60,
+            DecklinkDisplayModeId::HD1080p9590 => MODE_1080P_9590,
+            DecklinkDisplayModeId::HD1080p96 => MODE_1080P_96,
+            DecklinkDisplayModeId::HD1080p100 => MODE_1080P_100,
+            DecklinkDisplayModeId::HD1080p11988 => MODE_1080P_11988,
+            DecklinkDisplayModeId::HD1080p120 => MODE_1080P_120,
+            DecklinkDisplayModeId::UHD4K2160p9590 => MODE_4K_9590,
+            DecklinkDisplayModeId::UHD4K2160p96 => MODE_4K_96,
+            DecklinkDisplayModeId::UHD4K2160p100 => MODE_4K_100,
+            DecklinkDisplayModeId::UHD4K2160p11988 => MODE_4K_11988,
+            DecklinkDisplayModeId::UHD4K2160p120 => MODE_4K_120,
+            DecklinkDisplayModeId::Other(v) => v,
+        }
+    }
 }
 
 #[derive(FromPrimitive, PartialEq, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DecklinkFieldDominance {
     Unknown = sdk::_DecklinkFieldDominance_decklinkUnknownFieldDominance as isize,
     LowerFieldFirst = sdk::_DecklinkFieldDominance_decklinkLowerFieldFirst as isize,
@@ -57,6 +366,7 @@ pub enum DecklinkFieldDominance {
 
 bitflags! {
     #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct DecklinkDisplayModeFlag: u32 {
         const SUPPORTS_3D = sdk::_DecklinkDisplayModeFlags_decklinkDisplayModeSupports3D;
         const COLORSPACE_REC601 = sdk::_DecklinkDisplayModeFlags_decklinkDisplayModeColorspaceRec601;
@@ -64,6 +374,417 @@ bitflags! {
     }
 }
 
+/// A frame rate, expressed in the same way as the SDK as the duration of a frame in a timescale.
+/// For example 29.97fps is a frame duration of 1001 in a timescale of 30000.
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FrameRate {
+    frame_duration: i64,
+    timescale: i64,
+}
+
+impl FrameRate {
+    /// Create a frame rate, which returns `None` unless both values are positive
+    pub const fn new(frame_duration: i64, timescale: i64) -> Option<FrameRate> {
+        if frame_duration > 0 && timescale > 0 {
+            Some(FrameRate {
+                frame_duration,
+                timescale,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// For the rates of the known modes, which are all valid
+    const fn known(frame_duration: i64, timescale: i64) -> FrameRate {
+        FrameRate {
+            frame_duration,
+            timescale,
+        }
+    }
+
+    /// The duration of one frame, in `timescale`
+    pub fn duration(&self) -> i64 {
+        self.frame_duration
+    }
+    pub fn timescale(&self) -> i64 {
+        self.timescale
+    }
+
+    /// The number of frames per second
+    pub fn fps_f64(&self) -> f64 {
+        self.timescale as f64 / self.frame_duration as f64
+    }
+
+    /// The duration of one frame in another timescale.
+    /// This is rounded down if the frame does not last an exact number of units of `timescale`.
+    /// Returns `None` if `timescale` is not positive.
+    pub fn frame_duration(&self, timescale: i64) -> Option<i64> {
+        if timescale <= 0 {
+            return None;
+        }
+        let duration =
+            (self.frame_duration as i128 * timescale as i128).div_euclid(self.timescale as i128);
+        i64::try_from(duration).ok()
+    }
+
+    /// The time at which frame number `frames` starts, in `timescale`.
+    /// When the frame does not start on a whole unit of `timescale` this is rounded up to the first unit within the frame,
+    /// so that `time_to_frames` maps it back to the same frame.
+    /// This is calculated from the absolute frame number, so does not accumulate rounding errors.
+    /// Returns `None` if `timescale` is not positive, or the time does not fit.
+    pub fn frames_to_time(&self, frames: i64, timescale: i64) -> Option<i64> {
+        if timescale <= 0 {
+            return None;
+        }
+        let time = -(-(frames as i128) * self.frame_duration as i128 * timescale as i128)
+            .div_euclid(self.timescale as i128);
+        i64::try_from(time).ok()
+    }
+
+    /// The number of the frame which is being shown at `time` in `timescale`.
+    /// Times before zero belong to negative frame numbers, so this rounds down rather than towards zero.
+    /// Returns `None` if `timescale` is not positive.
+    pub fn time_to_frames(&self, time: i64, timescale: i64) -> Option<i64> {
+        if timescale <= 0 {
+            return None;
+        }
+        let frames = (time as i128 * self.timescale as i128)
+            .div_euclid(self.frame_duration as i128 * timescale as i128);
+        i64::try_from(frames).ok()
+    }
+
+    /// Whether this is a 1000/1001 rate, such as 29.97 or 23.98
+    pub fn is_fractional(&self) -> bool {
+        self.timescale.checked_rem(self.frame_duration).unwrap_or(0) != 0
+    }
+
+    /// The integer frame rate used for counting timecode, eg 30 for 29.97
+    pub fn nominal_fps(&self) -> u32 {
+        self.fps_f64().round() as u32
+    }
+
+    /// Whether timecode at this rate is normally drop-frame, as it is for 29.97 and 59.94
+    pub fn is_drop_frame_rate(&self) -> bool {
+        self.is_fractional() && self.nominal_fps() % 30 == 0
+    }
+}
+
+/// The properties of a display mode, which are known without asking a device
+#[derive(PartialEq, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DisplayModeProperties {
+    pub width: usize,
+    pub height: usize,
+    pub frame_rate: FrameRate,
+    pub field_dominance: DecklinkFieldDominance,
+    /// Either `COLORSPACE_REC601` or `COLORSPACE_REC709`
+    pub colorspace: DecklinkDisplayModeFlag,
+}
+
+impl DecklinkDisplayModeId {
+    /// The properties of this mode, as they are defined by the SDK.
+    /// This returns `None` for modes which have no fixed properties, such as `Unknown`.
+    pub fn properties(&self) -> Option<DisplayModeProperties> {
+        use DecklinkDisplayModeId::*;
+
+        let (width, height) = match self {
+            NTSC | NTSC2398 | NTSCp => (720, 486),
+            PAL | PALp => (720, 576),
+            HD720p50 | HD720p5994 | HD720p60 => (1280, 720),
+            HD1080p2398 | HD1080p24 | HD1080p25 | HD1080p2997 | HD1080p30 | HD1080i50
+            | HD1080i5994 | HD1080i6000 | HD1080p50 | HD1080p5994 | HD1080p6000 | HD1080p9590
+            | HD1080p96 | HD1080p100 | HD1080p11988 | HD1080p120 => (1920, 1080),
+            HD2k2398 | HD2k24 | HD2k25 => (2048, 1556),
+            HD2kDCI2398 | HD2kDCI24 | HD2kDCI25 | HD2kDCI2997 | HD2kDCI30 | HD2kDCI4795
+            | HD2kDCI48 | HD2kDCI50 | HD2kDCI5994 | HD2kDCI60 => (2048, 1080),
+            UHD4K2160p2398 | UHD4K2160p24 | UHD4K2160p25 | UHD4K2160p2997 | UHD4K2160p30
+            | UHD4K2160p50 | UHD4K2160p5994 | UHD4K2160p60 | UHD4K2160p9590 | UHD4K2160p96
+            | UHD4K2160p100 | UHD4K2160p11988 | UHD4K2160p120 => (3840, 2160),
+            UHD4KDCI2398 | UHD4KDCI24 | UHD4KDCI25 | UHD4KDCI2997 | UHD4KDCI30 | UHD4KDCI4795
+            | UHD4KDCI48 | UHD4KDCI50 | UHD4KDCI5994 | UHD4KDCI60 | UHD4KDCI9590 | UHD4KDCI96
+            | UHD4KDCI100 | UHD4KDCI11988 | UHD4KDCI120 => (4096, 2160),
+            UHD8K4320p2398 | UHD8K4320p24 | UHD8K4320p25 | UHD8K4320p2997 | UHD8K4320p30
+            | UHD8K4320p4795 | UHD8K4320p48 | UHD8K4320p50 | UHD8K4320p5994 | UHD8K4320p60 => {
+                (7680, 4320)
+            }
+            UHD8KDCI2398 | UHD8KDCI24 | UHD8KDCI25 | UHD8KDCI2997 | UHD8KDCI30 | UHD8KDCI4795
+            | UHD8KDCI48 | UHD8KDCI50 | UHD8KDCI5994 | UHD8KDCI60 => (8192, 4320),
+            CintelRAW | CintelCompressedRAW | Unknown | Other(_) => return None,
+        };
+
+        let frame_rate = match self {
+            NTSC2398 | HD1080p2398 | HD2k2398 | HD2kDCI2398 | UHD4K2160p2398 | UHD4KDCI2398
+            | UHD8K4320p2398 | UHD8KDCI2398 => FrameRate::known(1001, 24000),
+            HD1080p24 | HD2k24 | HD2kDCI24 | UHD4K2160p24 | UHD4KDCI24 | UHD8K4320p24
+            | UHD8KDCI24 => FrameRate::known(1000, 24000),
+            PAL | HD1080p25 | HD1080i50 | HD2k25 | HD2kDCI25 | UHD4K2160p25 | UHD4KDCI25
+            | UHD8K4320p25 | UHD8KDCI25 => FrameRate::known(1000, 25000),
+            NTSC | HD1080p2997 | HD1080i5994 | HD2kDCI2997 | UHD4K2160p2997 | UHD4KDCI2997
+            | UHD8K4320p2997 | UHD8KDCI2997 => FrameRate::known(1001, 30000),
+            HD1080p30 | HD1080i6000 | HD2kDCI30 | UHD4K2160p30 | UHD4KDCI30 | UHD8K4320p30
+            | UHD8KDCI30 => FrameRate::known(1000, 30000),
+            HD2kDCI4795 | UHD4KDCI4795 | UHD8K4320p4795 | UHD8KDCI4795 => {
+                FrameRate::known(1001, 48000)
+            }
+            HD2kDCI48 | UHD4KDCI48 | UHD8K4320p48 | UHD8KDCI48 => FrameRate::known(1000, 48000),
+            PALp | HD720p50 | HD1080p50 | HD2kDCI50 | UHD4K2160p50 | UHD4KDCI50 | UHD8K4320p50
+            | UHD8KDCI50 => FrameRate::known(1000, 50000),
+            NTSCp | HD720p5994 | HD1080p5994 | HD2kDCI5994 | UHD4K2160p5994 | UHD4KDCI5994
+            | UHD8K4320p5994 | UHD8KDCI5994 => FrameRate::known(1001, 60000),
+            HD720p60 | HD1080p6000 | HD2kDCI60 | UHD4K2160p60 | UHD4KDCI60 | UHD8K4320p60
+            | UHD8KDCI60 => FrameRate::known(1000, 60000),
+            HD1080p9590 | UHD4K2160p9590 | UHD4KDCI9590 => FrameRate::known(1001, 96000),
+            HD1080p96 | UHD4K2160p96 | UHD4KDCI96 => FrameRate::known(1000, 96000),
+            HD1080p100 | UHD4K2160p100 | UHD4KDCI100 => FrameRate::known(1000, 100000),
+            HD1080p11988 | UHD4K2160p11988 | UHD4KDCI11988 => FrameRate::known(1001, 120000),
+            HD1080p120 | UHD4K2160p120 | UHD4KDCI120 => FrameRate::known(1000, 120000),
+            CintelRAW | CintelCompressedRAW | Unknown | Other(_) => return None,
+        };
+
+        let field_dominance = match self {
+            NTSC | NTSC2398 => DecklinkFieldDominance::LowerFieldFirst,
+            PAL | HD1080i50 | HD1080i5994 | HD1080i6000 => DecklinkFieldDominance::UpperFieldFirst,
+            _ => DecklinkFieldDominance::ProgressiveFrame,
+        };
+
+        let colorspace = match self {
+            NTSC | NTSC2398 | NTSCp | PAL | PALp => DecklinkDisplayModeFlag::COLORSPACE_REC601,
+            _ => DecklinkDisplayModeFlag::COLORSPACE_REC709,
+        };
+
+        Some(DisplayModeProperties {
+            width,
+            height,
+            frame_rate,
+            field_dominance,
+            colorspace,
+        })
+    }
+}
+
+impl fmt::Display for DecklinkDisplayModeId {
+    /// Formats the mode with its broadcast name, such as `1080i50` or `2160p59.94`.
+    /// Interlaced modes use the field rate, and progressive modes the frame rate.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use DecklinkDisplayModeId::*;
+        match self {
+            NTSC => return write!(f, "NTSC"),
+            NTSC2398 => return write!(f, "NTSC23.98"),
+            NTSCp => return write!(f, "NTSCp"),
+            PAL => return write!(f, "PAL"),
+            PALp => return write!(f, "PALp"),
+            CintelRAW => return write!(f, "CintelRAW"),
+            CintelCompressedRAW => return write!(f, "CintelCompressedRAW"),
+            Unknown => return write!(f, "Unknown"),
+            Other(v) => return write!(f, "{:#010x}", v),
+            _ => {}
+        }
+
+        let properties = match self.properties() {
+            Some(properties) => properties,
+            None => return write!(f, "{:?}", self),
+        };
+
+        match properties.width {
+            1280 | 1920 | 3840 | 7680 => write!(f, "{}", properties.height)?,
+            _ => write!(f, "{}x{}", properties.width, properties.height)?,
+        }
+
+        let interlaced = matches!(
+            properties.field_dominance,
+            DecklinkFieldDominance::LowerFieldFirst | DecklinkFieldDominance::UpperFieldFirst
+        );
+        let (scan, rate) = if interlaced {
+            ("i", properties.frame_rate.fps_f64() * 2.0)
+        } else {
+            ("p", properties.frame_rate.fps_f64())
+        };
+
+        if properties.frame_rate.is_fractional() {
+            write!(f, "{}{:.2}", scan, rate)
+        } else {
+            write!(f, "{}{}", scan, rate.round())
+        }
+    }
+}
+
+/// The error returned when a string is not a recognised display mode
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct ParseDisplayModeIdError(String);
+
+impl fmt::Display for ParseDisplayModeIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown display mode: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseDisplayModeIdError {}
+
+impl FromStr for DecklinkDisplayModeId {
+    type Err = ParseDisplayModeIdError;
+
+    /// Parse a mode from its broadcast name, as formatted by `Display`.
+    /// The rate may be given more precisely than the name uses, such as `1080p23.976`,
+    /// and modes not known to the bindings can be given as their hex FourCC value.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+
+        if let Some(mode) =
+            DecklinkDisplayModeId::iter().find(|m| m.to_string().eq_ignore_ascii_case(trimmed))
+        {
+            return Ok(mode);
+        }
+
+        // Match on the resolution and rate, to allow for the rate being written differently
+        let lower = trimmed.to_ascii_lowercase();
+        if let Some(index) = lower.find(['p', 'i']) {
+            let (resolution, rest) = lower.split_at(index);
+            if let Ok(rate) = rest[1..].parse::<f64>() {
+                let found = DecklinkDisplayModeId::iter().find(|mode| {
+                    let name = mode.to_string().to_ascii_lowercase();
+                    match name.find(['p', 'i']) {
+                        Some(i) => {
+                            name[..i] == *resolution
+                                && name[i..i + 1] == rest[..1]
+                                && name[i + 1..]
+                                    .parse::<f64>()
+                                    .is_ok_and(|r| (r - rate).abs() < 0.01)
+                        }
+                        None => false,
+                    }
+                });
+                if let Some(mode) = found {
+                    return Ok(mode);
+                }
+            }
+        }
+
+        if let Some(hex) = lower.strip_prefix("0x") {
+            if let Ok(v) = u32::from_str_radix(hex, 16) {
+                return Ok(DecklinkDisplayModeId::from(v));
+            }
+        }
+
+        Err(ParseDisplayModeIdError(s.to_string()))
+    }
+}
+
+/// Whether a display mode is progressive or interlaced
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DisplayModeScan {
+    Progressive,
+    Interlaced,
+}
+
+impl DisplayModeProperties {
+    /// The rate used in the name of the mode, which is the field rate for interlaced modes (50 for 1080i50),
+    /// and the frame rate otherwise
+    pub fn name_rate(&self) -> f64 {
+        match self.field_dominance {
+            DecklinkFieldDominance::LowerFieldFirst | DecklinkFieldDominance::UpperFieldFirst => {
+                self.frame_rate.fps_f64() * 2.0
+            }
+            _ => self.frame_rate.fps_f64(),
+        }
+    }
+
+    fn matches_scan(&self, scan: Option<DisplayModeScan>) -> bool {
+        matches!(
+            (scan, self.field_dominance),
+            (None, _)
+                | (_, DecklinkFieldDominance::Unknown)
+                | (
+                    Some(DisplayModeScan::Interlaced),
+                    DecklinkFieldDominance::LowerFieldFirst
+                        | DecklinkFieldDominance::UpperFieldFirst,
+                )
+                | (
+                    Some(DisplayModeScan::Progressive),
+                    DecklinkFieldDominance::ProgressiveFrame
+                        | DecklinkFieldDominance::ProgressiveSegmentedFrame,
+                )
+        )
+    }
+}
+
+/// Pick the best of `modes` for the requested properties, as described by `find_display_mode`
+fn best_display_mode<T>(
+    modes: impl Iterator<Item = (T, DisplayModeProperties)>,
+    width: usize,
+    height: usize,
+    rate: f64,
+    scan: Option<DisplayModeScan>,
+) -> Option<T> {
+    // Rates are usually written to 2 decimal places, such as 23.98 for 24000/1001
+    const TOLERANCE: f64 = 0.01;
+    let fractional_rate = rate * 1000.0 / 1001.0;
+
+    modes
+        .filter(|(_, props)| props.width == width && props.height == height)
+        .filter(|(_, props)| props.matches_scan(scan))
+        .filter_map(|(mode, props)| {
+            let name_rate = props.name_rate();
+            let exact = (name_rate - rate).abs();
+            let fractional = (name_rate - fractional_rate).abs();
+            if exact < TOLERANCE {
+                Some((mode, (0, exact)))
+            } else if fractional < TOLERANCE {
+                Some((mode, (1, fractional)))
+            } else {
+                None
+            }
+        })
+        .min_by(|(_, a), (_, b)| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)))
+        .map(|(mode, _)| mode)
+}
+
+/// Find the mode that best matches the given properties.
+/// `rate` is the rate used in the name of the mode, so it is the field rate for interlaced modes (50 for 1080i50),
+/// and the frame rate for progressive modes. It is matched to within 0.01, so 29.97 will find a drop-frame rate.
+/// When no mode has the requested rate, the 1000/1001 variant of it is used instead, so 30 will find 29.97
+/// on a device which only offers that.
+/// If `scan` is `None`, progressive and interlaced modes are both considered.
+pub fn find_display_mode(
+    modes: &[DecklinkDisplayMode],
+    width: usize,
+    height: usize,
+    rate: f64,
+    scan: Option<DisplayModeScan>,
+) -> Option<&DecklinkDisplayMode> {
+    best_display_mode(
+        modes
+            .iter()
+            .filter_map(|mode| Some((mode, mode.properties()?))),
+        width,
+        height,
+        rate,
+        scan,
+    )
+}
+
+/// Find the mode that best matches the given properties, from a list of mode ids.
+/// This uses the properties defined by the SDK for each mode, and matches in the same way as `find_display_mode`.
+pub fn find_display_mode_id(
+    modes: &[DecklinkDisplayModeId],
+    width: usize,
+    height: usize,
+    rate: f64,
+    scan: Option<DisplayModeScan>,
+) -> Option<DecklinkDisplayModeId> {
+    best_display_mode(
+        modes
+            .iter()
+            .filter_map(|mode| Some((*mode, mode.properties()?))),
+        width,
+        height,
+        rate,
+        scan,
+    )
+}
+
 pub struct DecklinkDisplayMode {
     mode: *mut sdk::cdecklink_display_mode_t,
 }
@@ -77,6 +798,13 @@ impl Drop for DecklinkDisplayMode {
     }
 }
 
+impl Clone for DecklinkDisplayMode {
+    fn clone(&self) -> Self {
+        unsafe { sdk::cdecklink_display_mode_add_ref(self.mode) };
+        DecklinkDisplayMode { mode: self.mode }
+    }
+}
+
 impl DecklinkDisplayMode {
     pub fn name(&self) -> Option<String> {
         let mut s = null();
@@ -88,10 +816,9 @@ impl DecklinkDisplayMode {
         }
     }
     pub fn mode(&self) -> DecklinkDisplayModeId {
-        DecklinkDisplayModeId::from_u32(unsafe {
+        DecklinkDisplayModeId::from(unsafe {
             sdk::cdecklink_display_mode_get_display_mode(self.mode)
         })
-        .unwrap_or(DecklinkDisplayModeId::Unknown)
     }
     pub fn width(&self) -> usize {
         let width = unsafe { sdk::cdecklink_display_mode_get_width(self.mode) };
@@ -116,6 +843,11 @@ impl DecklinkDisplayMode {
             }
         }
     }
+    /// The frame rate of the mode, as a `FrameRate`
+    pub fn frame_rate(&self) -> Option<FrameRate> {
+        self.framerate()
+            .and_then(|(duration, scale)| FrameRate::new(duration, scale))
+    }
     pub fn field_dominance(&self) -> DecklinkFieldDominance {
         DecklinkFieldDominance::from_u32(unsafe {
             sdk::cdecklink_display_mode_get_field_dominance(self.mode)
@@ -127,6 +859,19 @@ impl DecklinkDisplayMode {
             sdk::cdecklink_display_mode_get_flags(self.mode)
         })
     }
+    /// The properties of the mode, as reported by the device.
+    /// This returns `None` if the device does not report a valid frame rate.
+    pub fn properties(&self) -> Option<DisplayModeProperties> {
+        Some(DisplayModeProperties {
+            width: self.width(),
+            height: self.height(),
+            frame_rate: self.frame_rate()?,
+            field_dominance: self.field_dominance(),
+            colorspace: self.flags()
+                & (DecklinkDisplayModeFlag::COLORSPACE_REC601
+                    | DecklinkDisplayModeFlag::COLORSPACE_REC709),
+        })
+    }
 }
 
 pub(crate) unsafe fn iterate_display_modes(
@@ -138,6 +883,7 @@ pub(crate) unsafe fn iterate_display_modes(
     loop {
         let ok2 = sdk::cdecklink_display_mode_iterator_next(it, &mut mode);
         if SdkError::is_ok(ok2) {
+            // The iterator hands over a reference to each mode
             res.push(DecklinkDisplayMode { mode })
         } else if SdkError::is_false(ok2) {
             break;
@@ -149,8 +895,231 @@ pub(crate) unsafe fn iterate_display_modes(
     Ok(res)
 }
 
+/// Wrap a raw pointer. This takes ownership of the reference, so a borrowed pointer must be `add_ref`ed first
 pub(crate) unsafe fn wrap_display_mode(
     ptr: *mut sdk::cdecklink_display_mode_t,
 ) -> DecklinkDisplayMode {
     DecklinkDisplayMode { mode: ptr }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use DecklinkDisplayModeId::*;
+    use DecklinkFieldDominance::{LowerFieldFirst, ProgressiveFrame, UpperFieldFirst};
+
+    #[test]
+    fn properties_match_sdk() {
+        // (mode, width, height, frame duration, timescale, field dominance)
+        let table = [
+            (NTSC, 720, 486, 1001, 30000, LowerFieldFirst),
+            (NTSC2398, 720, 486, 1001, 24000, LowerFieldFirst),
+            (NTSCp, 720, 486, 1001, 60000, ProgressiveFrame),
+            (PAL, 720, 576, 1000, 25000, UpperFieldFirst),
+            (PALp, 720, 576, 1000, 50000, ProgressiveFrame),
+            (HD720p50, 1280, 720, 1000, 50000, ProgressiveFrame),
+            (HD720p5994, 1280, 720, 1001, 60000, ProgressiveFrame),
+            (HD1080p2398, 1920, 1080, 1001, 24000, ProgressiveFrame),
+            (HD1080i50, 1920, 1080, 1000, 25000, UpperFieldFirst),
+            (HD1080i5994, 1920, 1080, 1001, 30000, UpperFieldFirst),
+            (HD1080p6000, 1920, 1080, 1000, 60000, ProgressiveFrame),
+            (HD2k24, 2048, 1556, 1000, 24000, ProgressiveFrame),
+            (HD2kDCI4795, 2048, 1080, 1001, 48000, ProgressiveFrame),
+            (UHD4K2160p5994, 3840, 2160, 1001, 60000, ProgressiveFrame),
+            (UHD4KDCI11988, 4096, 2160, 1001, 120000, ProgressiveFrame),
+            (UHD4KDCI100, 4096, 2160, 1000, 100000, ProgressiveFrame),
+            (UHD8K4320p25, 7680, 4320, 1000, 25000, ProgressiveFrame),
+            (UHD8KDCI2997, 8192, 4320, 1001, 30000, ProgressiveFrame),
+        ];
+
+        for (mode, width, height, duration, timescale, field_dominance) in table {
+            let properties = mode.properties().unwrap();
+            assert_eq!(properties.width, width, "{:?}", mode);
+            assert_eq!(properties.height, height, "{:?}", mode);
+            assert_eq!(
+                properties.frame_rate,
+                FrameRate::new(duration, timescale).unwrap(),
+                "{:?}",
+                mode
+            );
+            assert_eq!(properties.field_dominance, field_dominance, "{:?}", mode);
+        }
+
+        assert_eq!(
+            NTSCp.properties().unwrap().colorspace,
+            DecklinkDisplayModeFlag::COLORSPACE_REC601
+        );
+        assert_eq!(
+            HD720p50.properties().unwrap().colorspace,
+            DecklinkDisplayModeFlag::COLORSPACE_REC709
+        );
+        assert!(Unknown.properties().is_none());
+        assert!(CintelRAW.properties().is_none());
+    }
+
+    #[test]
+    fn names_round_trip() {
+        for mode in DecklinkDisplayModeId::iter() {
+            let name = mode.to_string();
+            assert_eq!(name.parse::<DecklinkDisplayModeId>(), Ok(mode), "{}", name);
+        }
+
+        let other = DecklinkDisplayModeId::Other(0x12345678);
+        assert_eq!(other.to_string().parse(), Ok(other));
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(HD1080i50.to_string(), "1080i50");
+        assert_eq!(HD1080i5994.to_string(), "1080i59.94");
+        assert_eq!(HD720p5994.to_string(), "720p59.94");
+        assert_eq!(UHD4K2160p11988.to_string(), "2160p119.88");
+        assert_eq!(UHD4KDCI24.to_string(), "4096x2160p24");
+        assert_eq!(NTSCp.to_string(), "NTSCp");
+
+        assert_eq!("1080p23.976".parse(), Ok(HD1080p2398));
+        assert_eq!(" 1080I50 ".parse(), Ok(HD1080i50));
+        assert_eq!("pal".parse(), Ok(PAL));
+        assert!("1080p51".parse::<DecklinkDisplayModeId>().is_err());
+    }
+
+    #[test]
+    fn find_display_mode_prefers_exact_rate() {
+        let modes = [HD1080p2997, HD1080p30, HD1080i5994, HD1080i50, HD720p60];
+
+        assert_eq!(
+            find_display_mode_id(&modes, 1920, 1080, 30.0, None),
+            Some(HD1080p30)
+        );
+        assert_eq!(
+            find_display_mode_id(&modes, 1920, 1080, 29.97, None),
+            Some(HD1080p2997)
+        );
+        assert_eq!(
+            find_display_mode_id(&modes, 1920, 1080, 50.0, Some(DisplayModeScan::Interlaced)),
+            Some(HD1080i50)
+        );
+        assert_eq!(
+            find_display_mode_id(&modes, 1280, 720, 60.0, Some(DisplayModeScan::Progressive)),
+            Some(HD720p60)
+        );
+    }
+
+    #[test]
+    fn find_display_mode_falls_back_to_fractional_rate() {
+        // A device which only offers the 1000/1001 rates
+        let modes = [
+            HD1080p2398,
+            HD1080p2997,
+            HD1080i5994,
+            HD1080p5994,
+            HD720p5994,
+        ];
+
+        assert_eq!(
+            find_display_mode_id(&modes, 1920, 1080, 24.0, None),
+            Some(HD1080p2398)
+        );
+        assert_eq!(
+            find_display_mode_id(&modes, 1920, 1080, 30.0, None),
+            Some(HD1080p2997)
+        );
+        assert_eq!(
+            find_display_mode_id(&modes, 1920, 1080, 60.0, Some(DisplayModeScan::Interlaced)),
+            Some(HD1080i5994)
+        );
+        assert_eq!(
+            find_display_mode_id(&modes, 1920, 1080, 60.0, Some(DisplayModeScan::Progressive)),
+            Some(HD1080p5994)
+        );
+        assert_eq!(
+            find_display_mode_id(&modes, 1280, 720, 60.0, None),
+            Some(HD720p5994)
+        );
+        // The fallback is only from a whole rate to its fractional variant
+        assert_eq!(
+            find_display_mode_id(&[HD1080p30], 1920, 1080, 29.97, None),
+            None
+        );
+    }
+
+    #[test]
+    fn find_display_mode_filters() {
+        let modes = [HD1080p25, HD1080i50, HD1080p50, HD720p50, Unknown];
+
+        // 1080i50 is named by its field rate, so is not found when asking for 25
+        assert_eq!(
+            find_display_mode_id(&modes, 1920, 1080, 25.0, None),
+            Some(HD1080p25)
+        );
+        assert_eq!(
+            find_display_mode_id(&modes, 1920, 1080, 50.0, Some(DisplayModeScan::Progressive)),
+            Some(HD1080p50)
+        );
+        assert_eq!(
+            find_display_mode_id(&modes, 1920, 1080, 25.0, Some(DisplayModeScan::Interlaced)),
+            None
+        );
+        assert_eq!(find_display_mode_id(&modes, 1280, 720, 25.0, None), None);
+        assert_eq!(find_display_mode_id(&modes, 3840, 2160, 50.0, None), None);
+        assert_eq!(find_display_mode_id(&[], 1920, 1080, 50.0, None), None);
+    }
+
+    #[test]
+    fn frame_rate_is_validated() {
+        assert!(FrameRate::new(0, 25000).is_none());
+        assert!(FrameRate::new(1000, 0).is_none());
+        assert!(FrameRate::new(-1000, 25000).is_none());
+
+        let rate = FrameRate::new(1000, 25000).unwrap();
+        assert_eq!(rate.frames_to_time(1, 0), None);
+        assert_eq!(rate.time_to_frames(1, 0), None);
+        assert_eq!(rate.frame_duration(-1), None);
+    }
+
+    #[test]
+    fn frame_rate_conversions() {
+        let ntsc = FrameRate::new(1001, 30000).unwrap();
+        assert!(ntsc.is_fractional());
+        assert!(ntsc.is_drop_frame_rate());
+        assert_eq!(ntsc.nominal_fps(), 30);
+        assert_eq!(ntsc.frame_duration(1000), Some(33));
+        assert_eq!(ntsc.frames_to_time(1, 1000), Some(34));
+        assert_eq!(ntsc.frames_to_time(-1, 1000), Some(-33));
+        assert_eq!(ntsc.frames_to_time(30, 30000), Some(30030));
+        assert_eq!(ntsc.time_to_frames(33, 1000), Some(0));
+        assert_eq!(ntsc.time_to_frames(-1, 1000), Some(-1));
+
+        let pal = FrameRate::new(1000, 25000).unwrap();
+        assert!(!pal.is_fractional());
+        assert!(!pal.is_drop_frame_rate());
+        assert_eq!(pal.frames_to_time(-3, 1000), Some(-120));
+        assert_eq!(pal.time_to_frames(-120, 1000), Some(-3));
+        assert_eq!(pal.time_to_frames(-119, 1000), Some(-3));
+        assert_eq!(pal.time_to_frames(-121, 1000), Some(-4));
+    }
+
+    #[test]
+    fn frame_rate_round_trips() {
+        let rates = [
+            FrameRate::new(1001, 30000).unwrap(),
+            FrameRate::new(1001, 24000).unwrap(),
+            FrameRate::new(1000, 50000).unwrap(),
+        ];
+        for rate in rates {
+            for timescale in [1000, 48000, 90000, rate.timescale()] {
+                for frames in -200..200 {
+                    let time = rate.frames_to_time(frames, timescale).unwrap();
+                    assert_eq!(
+                        rate.time_to_frames(time, timescale),
+                        Some(frames),
+                        "{:?} frame {} in {}",
+                        rate,
+                        frames,
+                        timescale
+                    );
+                }
+            }
+        }
+    }
+}
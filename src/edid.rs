@@ -0,0 +1,782 @@
+//! Parsing of the EDID reported by a connected HDMI sink (see `DecklinkDeviceStatus::received_edid`).
+//!
+//! Supports EDID 1.3/1.4 base blocks and CTA-861 extension blocks.
+
+use crate::display_mode::DecklinkDisplayModeId;
+
+const BLOCK_LENGTH: usize = 128;
+const HEADER: [u8; 8] = [0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00];
+const CTA_EXTENSION_TAG: u8 = 0x02;
+const HDMI_OUI: u32 = 0x000C03;
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum EdidError {
+    /// The data ended part way through a block
+    TooShort,
+    /// The base block does not begin with the EDID header
+    InvalidHeader,
+    /// The checksum of the block at this index does not match
+    InvalidChecksum(usize),
+}
+
+/// A detailed timing descriptor
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EdidDetailedTiming {
+    pub pixel_clock_khz: u32,
+    pub horizontal_active: u16,
+    pub horizontal_blanking: u16,
+    pub horizontal_sync_offset: u16,
+    pub horizontal_sync_width: u16,
+    /// The active lines of the frame, or of each field when interlaced
+    pub vertical_active: u16,
+    pub vertical_blanking: u16,
+    pub vertical_sync_offset: u16,
+    pub vertical_sync_width: u16,
+    pub interlaced: bool,
+}
+
+impl EdidDetailedTiming {
+    fn parse(bytes: &[u8]) -> Option<EdidDetailedTiming> {
+        let pixel_clock = u16::from_le_bytes([bytes[0], bytes[1]]);
+        if pixel_clock == 0 {
+            // Not a timing, but a display descriptor
+            return None;
+        }
+
+        Some(EdidDetailedTiming {
+            pixel_clock_khz: pixel_clock as u32 * 10,
+            horizontal_active: bytes[2] as u16 | ((bytes[4] as u16 & 0xF0) << 4),
+            horizontal_blanking: bytes[3] as u16 | ((bytes[4] as u16 & 0x0F) << 8),
+            horizontal_sync_offset: bytes[8] as u16 | ((bytes[11] as u16 & 0xC0) << 2),
+            horizontal_sync_width: bytes[9] as u16 | ((bytes[11] as u16 & 0x30) << 4),
+            vertical_active: bytes[5] as u16 | ((bytes[7] as u16 & 0xF0) << 4),
+            vertical_blanking: bytes[6] as u16 | ((bytes[7] as u16 & 0x0F) << 8),
+            vertical_sync_offset: (bytes[10] as u16 >> 4) | ((bytes[11] as u16 & 0x0C) << 2),
+            vertical_sync_width: (bytes[10] as u16 & 0x0F) | ((bytes[11] as u16 & 0x03) << 4),
+            interlaced: bytes[17] & 0x80 != 0,
+        })
+    }
+
+    /// The height of the complete frame
+    pub fn height(&self) -> u32 {
+        if self.interlaced {
+            self.vertical_active as u32 * 2
+        } else {
+            self.vertical_active as u32
+        }
+    }
+
+    /// The number of frames per second
+    pub fn frame_rate(&self) -> f64 {
+        let h_total = (self.horizontal_active + self.horizontal_blanking) as f64;
+        let v_total = (self.vertical_active + self.vertical_blanking) as f64;
+        if h_total == 0.0 || v_total == 0.0 {
+            return 0.0;
+        }
+
+        let field_rate = self.pixel_clock_khz as f64 * 1000.0 / (h_total * v_total);
+        if self.interlaced {
+            field_rate / 2.0
+        } else {
+            field_rate
+        }
+    }
+}
+
+/// A video format from a CTA-861 video data block
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EdidVideoFormat {
+    /// The CTA-861 video identification code
+    pub vic: u8,
+    /// Whether the sink reports this as a native format
+    pub native: bool,
+}
+
+impl EdidVideoFormat {
+    /// The display modes that match this format.
+    /// Sinks that support the integer rates also support the 1000/1001 rates, so both are reported.
+    pub fn display_modes(&self) -> &'static [DecklinkDisplayModeId] {
+        vic_display_modes(self.vic)
+    }
+}
+
+fn vic_display_modes(vic: u8) -> &'static [DecklinkDisplayModeId] {
+    use DecklinkDisplayModeId::*;
+    match vic {
+        // 480p59.94 and 576p50 are carried over SDI as the 486 and 576 line progressive modes
+        2 | 3 => &[NTSCp],
+        4 => &[HD720p60, HD720p5994],
+        5 => &[HD1080i6000, HD1080i5994],
+        6 | 7 => &[NTSC],
+        16 => &[HD1080p6000, HD1080p5994],
+        17 | 18 => &[PALp],
+        19 => &[HD720p50],
+        20 => &[HD1080i50],
+        21 | 22 => &[PAL],
+        31 => &[HD1080p50],
+        32 => &[HD1080p24, HD1080p2398],
+        33 => &[HD1080p25],
+        34 => &[HD1080p30, HD1080p2997],
+        // 720p24, 720p25 and 720p30 have no decklink mode
+        60..=62 => &[],
+        63 => &[HD1080p120, HD1080p11988],
+        64 => &[HD1080p100],
+        93 => &[UHD4K2160p24, UHD4K2160p2398],
+        94 => &[UHD4K2160p25],
+        95 => &[UHD4K2160p30, UHD4K2160p2997],
+        96 => &[UHD4K2160p50],
+        97 => &[UHD4K2160p60, UHD4K2160p5994],
+        98 => &[UHD4KDCI24, UHD4KDCI2398],
+        99 => &[UHD4KDCI25],
+        100 => &[UHD4KDCI30, UHD4KDCI2997],
+        101 => &[UHD4KDCI50],
+        102 => &[UHD4KDCI60, UHD4KDCI5994],
+        117 => &[UHD4K2160p100],
+        118 => &[UHD4K2160p120, UHD4K2160p11988],
+        194 => &[UHD8K4320p24, UHD8K4320p2398],
+        195 => &[UHD8K4320p25],
+        196 => &[UHD8K4320p30, UHD8K4320p2997],
+        197 => &[UHD8K4320p48, UHD8K4320p4795],
+        198 => &[UHD8K4320p50],
+        199 => &[UHD8K4320p60, UHD8K4320p5994],
+        _ => &[],
+    }
+}
+
+/// Convert a HDMI 1.4 extended resolution to its equivalent CTA-861 video identification code
+fn hdmi_vic_to_vic(hdmi_vic: u8) -> Option<u8> {
+    match hdmi_vic {
+        1 => Some(95),
+        2 => Some(94),
+        3 => Some(93),
+        4 => Some(98),
+        _ => None,
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EdidAudioFormatCode {
+    Lpcm,
+    Ac3,
+    Mpeg1,
+    Mp3,
+    Mpeg2,
+    AacLc,
+    Dts,
+    Atrac,
+    OneBitAudio,
+    EnhancedAc3,
+    DtsHd,
+    Mat,
+    Dst,
+    WmaPro,
+    Other(u8),
+}
+impl From<u8> for EdidAudioFormatCode {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => EdidAudioFormatCode::Lpcm,
+            2 => EdidAudioFormatCode::Ac3,
+            3 => EdidAudioFormatCode::Mpeg1,
+            4 => EdidAudioFormatCode::Mp3,
+            5 => EdidAudioFormatCode::Mpeg2,
+            6 => EdidAudioFormatCode::AacLc,
+            7 => EdidAudioFormatCode::Dts,
+            8 => EdidAudioFormatCode::Atrac,
+            9 => EdidAudioFormatCode::OneBitAudio,
+            10 => EdidAudioFormatCode::EnhancedAc3,
+            11 => EdidAudioFormatCode::DtsHd,
+            12 => EdidAudioFormatCode::Mat,
+            13 => EdidAudioFormatCode::Dst,
+            14 => EdidAudioFormatCode::WmaPro,
+            v => EdidAudioFormatCode::Other(v),
+        }
+    }
+}
+
+bitflags! {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct EdidAudioSampleRates: u8 {
+        const RATE_32KHZ = 1 << 0;
+        const RATE_44_1KHZ = 1 << 1;
+        const RATE_48KHZ = 1 << 2;
+        const RATE_88_2KHZ = 1 << 3;
+        const RATE_96KHZ = 1 << 4;
+        const RATE_176_4KHZ = 1 << 5;
+        const RATE_192KHZ = 1 << 6;
+    }
+}
+
+bitflags! {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct EdidLpcmBitDepths: u8 {
+        const BITS_16 = 1 << 0;
+        const BITS_20 = 1 << 1;
+        const BITS_24 = 1 << 2;
+    }
+}
+
+/// An audio format from a CTA-861 short audio descriptor
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EdidAudioFormat {
+    pub format: EdidAudioFormatCode,
+    pub max_channels: u8,
+    pub sample_rates: EdidAudioSampleRates,
+    /// The supported bit depths, only reported for LPCM
+    pub bit_depths: Option<EdidLpcmBitDepths>,
+    /// The maximum bitrate, only reported for some compressed formats
+    pub max_bitrate_kbps: Option<u32>,
+}
+
+impl EdidAudioFormat {
+    fn parse(bytes: &[u8]) -> EdidAudioFormat {
+        let code = (bytes[0] >> 3) & 0x0F;
+        let format = EdidAudioFormatCode::from(code);
+        EdidAudioFormat {
+            format,
+            max_channels: (bytes[0] & 0x07) + 1,
+            sample_rates: EdidAudioSampleRates::from_bits_truncate(bytes[1]),
+            bit_depths: match format {
+                EdidAudioFormatCode::Lpcm => Some(EdidLpcmBitDepths::from_bits_truncate(bytes[2])),
+                _ => None,
+            },
+            max_bitrate_kbps: match code {
+                2..=8 => Some(bytes[2] as u32 * 8),
+                _ => None,
+            },
+        }
+    }
+}
+
+bitflags! {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct EdidHdrEotf: u8 {
+        const TRADITIONAL_SDR = 1 << 0;
+        const TRADITIONAL_HDR = 1 << 1;
+        const SMPTE_ST2084 = 1 << 2;
+        const HLG = 1 << 3;
+    }
+}
+
+/// The contents of a CTA-861 HDR static metadata data block
+#[derive(PartialEq, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EdidHdrStaticMetadata {
+    pub eotfs: EdidHdrEotf,
+    /// Whether static metadata type 1 (SMPTE ST 2086) is supported
+    pub static_metadata_type1: bool,
+    /// The desired content max luminance, in cd/m2
+    pub max_luminance: Option<f64>,
+    /// The desired content max frame-average luminance, in cd/m2
+    pub max_frame_average_luminance: Option<f64>,
+    /// The desired content min luminance, in cd/m2
+    pub min_luminance: Option<f64>,
+}
+
+impl EdidHdrStaticMetadata {
+    fn parse(bytes: &[u8]) -> Option<EdidHdrStaticMetadata> {
+        if bytes.len() < 2 {
+            return None;
+        }
+
+        let luminance = |cv: u8| 50.0 * 2f64.powf(cv as f64 / 32.0);
+        let max_luminance = bytes.get(2).map(|cv| luminance(*cv));
+        let max_frame_average_luminance = bytes.get(3).map(|cv| luminance(*cv));
+        let min_luminance = match (max_luminance, bytes.get(4)) {
+            (Some(max), Some(cv)) => Some(max * (*cv as f64 / 255.0).powi(2) / 100.0),
+            _ => None,
+        };
+
+        Some(EdidHdrStaticMetadata {
+            eotfs: EdidHdrEotf::from_bits_truncate(bytes[0]),
+            static_metadata_type1: bytes[1] & 0x01 != 0,
+            max_luminance,
+            max_frame_average_luminance,
+            min_luminance,
+        })
+    }
+}
+
+/// A parsed EDID
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Edid {
+    /// The three letter PNP id of the manufacturer
+    pub manufacturer_id: String,
+    pub product_code: u16,
+    pub serial_number: u32,
+    pub manufacture_week: u8,
+    pub manufacture_year: u16,
+    /// The EDID version and revision, eg (1, 4)
+    pub version: (u8, u8),
+
+    pub monitor_name: Option<String>,
+    pub monitor_serial: Option<String>,
+
+    /// The timing the display prefers, normally its native resolution
+    pub preferred_timing: Option<EdidDetailedTiming>,
+    /// All detailed timings from the base block and extensions, including the preferred timing
+    pub detailed_timings: Vec<EdidDetailedTiming>,
+
+    pub video_formats: Vec<EdidVideoFormat>,
+    pub hdr_static_metadata: Option<EdidHdrStaticMetadata>,
+    pub audio_formats: Vec<EdidAudioFormat>,
+}
+
+fn validate_block(block: &[u8], index: usize) -> Result<(), EdidError> {
+    let sum = block.iter().fold(0u8, |acc, v| acc.wrapping_add(*v));
+    if sum == 0 {
+        Ok(())
+    } else {
+        Err(EdidError::InvalidChecksum(index))
+    }
+}
+
+fn parse_descriptor_text(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|b| *b == 0x0A).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end])
+        .trim_end()
+        .to_string()
+}
+
+impl Edid {
+    /// Parse an EDID, including any CTA-861 extension blocks.
+    /// Extension blocks of other types are ignored.
+    pub fn parse(bytes: &[u8]) -> Result<Edid, EdidError> {
+        if bytes.len() < BLOCK_LENGTH {
+            return Err(EdidError::TooShort);
+        }
+
+        let base = &bytes[0..BLOCK_LENGTH];
+        if base[0..8] != HEADER {
+            return Err(EdidError::InvalidHeader);
+        }
+        validate_block(base, 0)?;
+
+        let manufacturer = u16::from_be_bytes([base[8], base[9]]);
+        let manufacturer_id = [10, 5, 0]
+            .iter()
+            .map(|shift| (b'A' - 1 + ((manufacturer >> shift) & 0x1F) as u8) as char)
+            .collect();
+
+        let mut edid = Edid {
+            manufacturer_id,
+            product_code: u16::from_le_bytes([base[10], base[11]]),
+            serial_number: u32::from_le_bytes([base[12], base[13], base[14], base[15]]),
+            manufacture_week: base[16],
+            manufacture_year: base[17] as u16 + 1990,
+            version: (base[18], base[19]),
+            monitor_name: None,
+            monitor_serial: None,
+            preferred_timing: None,
+            detailed_timings: Vec::new(),
+            video_formats: Vec::new(),
+            hdr_static_metadata: None,
+            audio_formats: Vec::new(),
+        };
+
+        for (i, descriptor) in base[54..126].chunks_exact(18).enumerate() {
+            if let Some(timing) = EdidDetailedTiming::parse(descriptor) {
+                // The first descriptor is always the preferred timing since EDID 1.3
+                if i == 0 {
+                    edid.preferred_timing = Some(timing);
+                }
+                edid.detailed_timings.push(timing);
+            } else {
+                match descriptor[3] {
+                    0xFC => edid.monitor_name = Some(parse_descriptor_text(&descriptor[5..])),
+                    0xFF => edid.monitor_serial = Some(parse_descriptor_text(&descriptor[5..])),
+                    _ => {}
+                }
+            }
+        }
+
+        let extension_count = base[126] as usize;
+        for index in 1..=extension_count {
+            let block = bytes
+                .get(index * BLOCK_LENGTH..(index + 1) * BLOCK_LENGTH)
+                .ok_or(EdidError::TooShort)?;
+            validate_block(block, index)?;
+
+            if block[0] == CTA_EXTENSION_TAG {
+                edid.parse_cta_extension(block);
+            }
+        }
+
+        Ok(edid)
+    }
+
+    fn parse_cta_extension(&mut self, block: &[u8]) {
+        let dtd_offset = (block[2] as usize).min(127);
+
+        // Revisions before 3 have no data block collection
+        if block[1] >= 3 && dtd_offset > 4 {
+            let mut offset = 4;
+            while offset < dtd_offset {
+                let tag = block[offset] >> 5;
+                let length = (block[offset] & 0x1F) as usize;
+                let end = (offset + 1 + length).min(dtd_offset);
+                self.parse_cta_data_block(tag, &block[offset + 1..end]);
+                offset += 1 + length;
+            }
+        }
+
+        if dtd_offset >= 4 {
+            for descriptor in block[dtd_offset..127].chunks_exact(18) {
+                match EdidDetailedTiming::parse(descriptor) {
+                    Some(timing) => self.detailed_timings.push(timing),
+                    None => break,
+                }
+            }
+        }
+    }
+
+    fn parse_cta_data_block(&mut self, tag: u8, payload: &[u8]) {
+        match tag {
+            // Audio data block
+            1 => {
+                for sad in payload.chunks_exact(3) {
+                    self.audio_formats.push(EdidAudioFormat::parse(sad));
+                }
+            }
+            // Video data block
+            2 => {
+                for svd in payload {
+                    let format = match *svd {
+                        0 | 128 | 254 | 255 => continue,
+                        1..=64 => EdidVideoFormat {
+                            vic: *svd,
+                            native: false,
+                        },
+                        129..=192 => EdidVideoFormat {
+                            vic: *svd & 0x7F,
+                            native: true,
+                        },
+                        vic => EdidVideoFormat { vic, native: false },
+                    };
+                    self.push_video_format(format);
+                }
+            }
+            // Vendor specific data block
+            3 => self.parse_vendor_data_block(payload),
+            // Extended tag
+            7 => {
+                if let Some((&extended_tag, payload)) = payload.split_first() {
+                    if extended_tag == 6 {
+                        self.hdr_static_metadata = EdidHdrStaticMetadata::parse(payload);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn parse_vendor_data_block(&mut self, payload: &[u8]) {
+        if payload.len() < 8 {
+            return;
+        }
+        let oui = u32::from_le_bytes([payload[0], payload[1], payload[2], 0]);
+        if oui != HDMI_OUI {
+            return;
+        }
+
+        // Walk over the optional fields to find the HDMI VICs
+        let flags = payload[7];
+        let mut offset = 8;
+        if flags & 0x80 != 0 {
+            // Video and audio latency
+            offset += 2;
+        }
+        if flags & 0x40 != 0 {
+            // Interlaced video and audio latency
+            offset += 2;
+        }
+        if flags & 0x20 == 0 {
+            // No HDMI video present
+            return;
+        }
+
+        let hdmi_vic_length = match payload.get(offset + 1) {
+            Some(v) => (v >> 5) as usize,
+            None => return,
+        };
+        let start = offset + 2;
+        let hdmi_vics = payload.get(start..start + hdmi_vic_length).unwrap_or(&[]);
+        for hdmi_vic in hdmi_vics {
+            if let Some(vic) = hdmi_vic_to_vic(*hdmi_vic) {
+                self.push_video_format(EdidVideoFormat { vic, native: false });
+            }
+        }
+    }
+
+    fn push_video_format(&mut self, format: EdidVideoFormat) {
+        match self.video_formats.iter_mut().find(|f| f.vic == format.vic) {
+            Some(existing) => existing.native |= format.native,
+            None => self.video_formats.push(format),
+        }
+    }
+
+    /// The display modes that the sink reports support for, with any native formats first
+    pub fn display_modes(&self) -> Vec<DecklinkDisplayModeId> {
+        let mut formats = self.video_formats.clone();
+        formats.sort_by_key(|f| !f.native);
+
+        let mut modes: Vec<DecklinkDisplayModeId> = Vec::new();
+        for format in formats {
+            for mode in format.display_modes() {
+                if !modes.contains(mode) {
+                    modes.push(*mode);
+                }
+            }
+        }
+        modes
+    }
+
+    /// Check whether the sink reports support for a display mode
+    pub fn supports_display_mode(&self, mode: DecklinkDisplayModeId) -> bool {
+        self.video_formats
+            .iter()
+            .any(|f| f.display_modes().contains(&mode))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display_mode::{DecklinkDisplayModeId::*, DecklinkFieldDominance};
+
+    /// A 1080p television, with a CTA-861 extension
+    const TV_EDID: [u8; 256] = [
+        0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x4C, 0x2D, 0x52, 0x0C, 0x01, 0x00, 0x00,
+        0x00, 0x1E, 0x1C, 0x01, 0x03, 0x80, 0xA0, 0x5A, 0x78, 0x0A, 0xEE, 0x91, 0xA3, 0x54, 0x4C,
+        0x99, 0x26, 0x0F, 0x50, 0x54, 0xBD, 0xEF, 0x80, 0x71, 0x4F, 0x81, 0xC0, 0x81, 0x00, 0x81,
+        0x80, 0x95, 0x00, 0xA9, 0xC0, 0xB3, 0x00, 0x01, 0x01, 0x02, 0x3A, 0x80, 0x18, 0x71, 0x38,
+        0x2D, 0x40, 0x58, 0x2C, 0x45, 0x00, 0xA0, 0x5A, 0x00, 0x00, 0x00, 0x1E, 0x01, 0x1D, 0x80,
+        0x18, 0x71, 0x1C, 0x16, 0x20, 0x58, 0x2C, 0x25, 0x00, 0xA0, 0x5A, 0x00, 0x00, 0x00, 0x9E,
+        0x00, 0x00, 0x00, 0xFD, 0x00, 0x18, 0x4B, 0x0F, 0x51, 0x17, 0x00, 0x0A, 0x20, 0x20, 0x20,
+        0x20, 0x20, 0x20, 0x00, 0x00, 0x00, 0xFC, 0x00, 0x53, 0x41, 0x4D, 0x53, 0x55, 0x4E, 0x47,
+        0x0A, 0x20, 0x20, 0x20, 0x20, 0x20, 0x01, 0x73, 0x02, 0x03, 0x31, 0xF0, 0x4D, 0x90, 0x04,
+        0x05, 0x1F, 0x20, 0x22, 0x13, 0x14, 0x61, 0x5E, 0x5F, 0x3F, 0x75, 0x26, 0x09, 0x07, 0x07,
+        0x15, 0x07, 0x50, 0x83, 0x01, 0x00, 0x00, 0x6C, 0x03, 0x0C, 0x00, 0x10, 0x00, 0x00, 0x3C,
+        0x20, 0x00, 0x40, 0x03, 0x04, 0xE6, 0x06, 0x0D, 0x01, 0x60, 0x50, 0x00, 0x01, 0x1D, 0x00,
+        0x72, 0x51, 0xD0, 0x1E, 0x20, 0x6E, 0x28, 0x55, 0x00, 0x20, 0xC2, 0x31, 0x00, 0x00, 0x1E,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x8A,
+    ];
+
+    fn fix_checksum(block: &mut [u8]) {
+        let sum = block[..127].iter().fold(0u8, |acc, v| acc.wrapping_add(*v));
+        block[127] = 0u8.wrapping_sub(sum);
+    }
+
+    #[test]
+    fn base_block() {
+        let mut bytes = TV_EDID[..128].to_vec();
+        // No extensions
+        bytes[126] = 0;
+        fix_checksum(&mut bytes);
+
+        let edid = Edid::parse(&bytes).unwrap();
+        assert_eq!(edid.manufacturer_id, "SAM");
+        assert_eq!(edid.product_code, 0x0C52);
+        assert_eq!(edid.serial_number, 1);
+        assert_eq!(edid.manufacture_week, 30);
+        assert_eq!(edid.manufacture_year, 2018);
+        assert_eq!(edid.version, (1, 3));
+        assert_eq!(edid.monitor_name.as_deref(), Some("SAMSUNG"));
+        assert_eq!(edid.monitor_serial, None);
+
+        let preferred = edid.preferred_timing.unwrap();
+        assert_eq!(preferred.pixel_clock_khz, 148_500);
+        assert_eq!(preferred.horizontal_active, 1920);
+        assert_eq!(preferred.horizontal_blanking, 280);
+        assert_eq!(preferred.horizontal_sync_offset, 88);
+        assert_eq!(preferred.horizontal_sync_width, 44);
+        assert_eq!(preferred.vertical_active, 1080);
+        assert_eq!(preferred.vertical_blanking, 45);
+        assert_eq!(preferred.vertical_sync_offset, 4);
+        assert_eq!(preferred.vertical_sync_width, 5);
+        assert!(!preferred.interlaced);
+        assert!((preferred.frame_rate() - 60.0).abs() < 0.01);
+
+        assert_eq!(edid.detailed_timings.len(), 2);
+        let interlaced = edid.detailed_timings[1];
+        assert!(interlaced.interlaced);
+        assert_eq!(interlaced.height(), 1080);
+        assert!((interlaced.frame_rate() - 30.0).abs() < 0.1);
+
+        assert!(edid.video_formats.is_empty());
+        assert!(edid.audio_formats.is_empty());
+        assert_eq!(edid.hdr_static_metadata, None);
+    }
+
+    #[test]
+    fn cta_extension() {
+        let edid = Edid::parse(&TV_EDID).unwrap();
+
+        // Two from the base block, and one from the extension
+        assert_eq!(edid.detailed_timings.len(), 3);
+        assert_eq!(edid.detailed_timings[2].horizontal_active, 1280);
+        assert_eq!(edid.detailed_timings[2].vertical_active, 720);
+
+        assert_eq!(
+            edid.video_formats[0],
+            EdidVideoFormat {
+                vic: 16,
+                native: true
+            }
+        );
+        assert_eq!(edid.video_formats.iter().filter(|f| f.native).count(), 1);
+        assert_eq!(&edid.display_modes()[..2], &[HD1080p6000, HD1080p5994]);
+        assert!(edid.supports_display_mode(HD1080i50));
+        assert!(edid.supports_display_mode(HD1080p120));
+        assert!(edid.supports_display_mode(UHD4K2160p100));
+        assert!(!edid.supports_display_mode(PAL));
+
+        assert_eq!(
+            edid.audio_formats,
+            vec![
+                EdidAudioFormat {
+                    format: EdidAudioFormatCode::Lpcm,
+                    max_channels: 2,
+                    sample_rates: EdidAudioSampleRates::RATE_32KHZ
+                        | EdidAudioSampleRates::RATE_44_1KHZ
+                        | EdidAudioSampleRates::RATE_48KHZ,
+                    bit_depths: Some(EdidLpcmBitDepths::all()),
+                    max_bitrate_kbps: None,
+                },
+                EdidAudioFormat {
+                    format: EdidAudioFormatCode::Ac3,
+                    max_channels: 6,
+                    sample_rates: EdidAudioSampleRates::RATE_32KHZ
+                        | EdidAudioSampleRates::RATE_44_1KHZ
+                        | EdidAudioSampleRates::RATE_48KHZ,
+                    bit_depths: None,
+                    max_bitrate_kbps: Some(640),
+                },
+            ]
+        );
+
+        let hdr = edid.hdr_static_metadata.unwrap();
+        assert_eq!(
+            hdr.eotfs,
+            EdidHdrEotf::TRADITIONAL_SDR | EdidHdrEotf::SMPTE_ST2084 | EdidHdrEotf::HLG
+        );
+        assert!(hdr.static_metadata_type1);
+        assert!((hdr.max_luminance.unwrap() - 400.0).abs() < 0.01);
+        assert!((hdr.max_frame_average_luminance.unwrap() - 282.84).abs() < 0.01);
+        assert_eq!(hdr.min_luminance, Some(0.0));
+    }
+
+    #[test]
+    fn hdmi_vendor_block() {
+        let edid = Edid::parse(&TV_EDID).unwrap();
+
+        // HDMI VICs 3 and 4 are 2160p24 and 4096x2160p24, which are not in the video data block
+        let vics: Vec<u8> = edid.video_formats.iter().map(|f| f.vic).collect();
+        assert!(vics.contains(&93));
+        assert!(vics.contains(&98));
+        assert!(edid.supports_display_mode(UHD4K2160p2398));
+        assert!(edid.supports_display_mode(UHD4KDCI24));
+
+        // 2160p25 and 2160p30 are listed in both, but only reported once
+        assert_eq!(vics.iter().filter(|vic| **vic == 95).count(), 1);
+    }
+
+    #[test]
+    fn invalid_data() {
+        assert_eq!(Edid::parse(&[]), Err(EdidError::TooShort));
+        assert_eq!(Edid::parse(&TV_EDID[..100]), Err(EdidError::TooShort));
+        // The extension block is missing
+        assert_eq!(Edid::parse(&TV_EDID[..200]), Err(EdidError::TooShort));
+
+        let mut bytes = TV_EDID;
+        bytes[0] = 0x01;
+        assert_eq!(Edid::parse(&bytes), Err(EdidError::InvalidHeader));
+
+        let mut bytes = TV_EDID;
+        bytes[20] ^= 0x01;
+        assert_eq!(Edid::parse(&bytes), Err(EdidError::InvalidChecksum(0)));
+
+        let mut bytes = TV_EDID;
+        bytes[200] ^= 0x01;
+        assert_eq!(Edid::parse(&bytes), Err(EdidError::InvalidChecksum(1)));
+    }
+
+    #[test]
+    fn vic_modes_match_properties() {
+        // (vic, width, height, field or frame rate, interlaced) from CTA-861
+        let formats = [
+            (2, 720, 480, 60, false),
+            (4, 1280, 720, 60, false),
+            (5, 1920, 1080, 60, true),
+            (6, 720, 480, 60, true),
+            (16, 1920, 1080, 60, false),
+            (17, 720, 576, 50, false),
+            (19, 1280, 720, 50, false),
+            (20, 1920, 1080, 50, true),
+            (21, 720, 576, 50, true),
+            (31, 1920, 1080, 50, false),
+            (32, 1920, 1080, 24, false),
+            (33, 1920, 1080, 25, false),
+            (34, 1920, 1080, 30, false),
+            (63, 1920, 1080, 120, false),
+            (64, 1920, 1080, 100, false),
+            (93, 3840, 2160, 24, false),
+            (96, 3840, 2160, 50, false),
+            (97, 3840, 2160, 60, false),
+            (98, 4096, 2160, 24, false),
+            (102, 4096, 2160, 60, false),
+            (117, 3840, 2160, 100, false),
+            (118, 3840, 2160, 120, false),
+            (194, 7680, 4320, 24, false),
+            (199, 7680, 4320, 60, false),
+        ];
+
+        for (vic, width, height, rate, interlaced) in formats {
+            let modes = vic_display_modes(vic);
+            assert!(!modes.is_empty(), "VIC {}", vic);
+
+            for mode in modes {
+                let properties = mode.properties().unwrap();
+                assert_eq!(properties.width, width, "VIC {} {:?}", vic, mode);
+                // SDI carries 486 lines for 480 line formats
+                let expected_height = if height == 480 { 486 } else { height };
+                assert_eq!(properties.height, expected_height, "VIC {} {:?}", vic, mode);
+
+                let mode_interlaced = matches!(
+                    properties.field_dominance,
+                    DecklinkFieldDominance::LowerFieldFirst
+                        | DecklinkFieldDominance::UpperFieldFirst
+                );
+                assert_eq!(mode_interlaced, interlaced, "VIC {} {:?}", vic, mode);
+
+                let fps = properties.frame_rate.fps_f64();
+                let mode_rate = if mode_interlaced { fps * 2.0 } else { fps };
+                let rate = rate as f64;
+                assert!(
+                    (mode_rate - rate).abs() < 0.01 || (mode_rate - rate / 1.001).abs() < 0.01,
+                    "VIC {} {:?} runs at {}",
+                    vic,
+                    mode,
+                    mode_rate
+                );
+            }
+        }
+
+        // 720p at 24, 25 and 30 have no decklink mode
+        for vic in 60..=62 {
+            assert!(vic_display_modes(vic).is_empty());
+        }
+    }
+}
@@ -1,27 +1,84 @@
 use crate::{sdk, SdkError};
 use aligned_vec::{AVec, ConstAlign};
-use num_traits::FromPrimitive;
+use std::marker::PhantomData;
 use std::ptr::null_mut;
+use std::sync::Arc;
 
-#[derive(EnumIter, FromPrimitive, PartialEq, Debug, Copy, Clone)]
+#[derive(EnumIter, PartialEq, Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DecklinkPixelFormat {
-    Format8BitYUV = sdk::_DecklinkPixelFormat_decklinkFormat8BitYUV as isize,
-    Format10BitYUV = sdk::_DecklinkPixelFormat_decklinkFormat10BitYUV as isize,
-    Format8BitARGB = sdk::_DecklinkPixelFormat_decklinkFormat8BitARGB as isize,
-    Format8BitBGRA = sdk::_DecklinkPixelFormat_decklinkFormat8BitBGRA as isize,
-    Format10BitRGB = sdk::_DecklinkPixelFormat_decklinkFormat10BitRGB as isize,
-    Format12BitRGB = sdk::_DecklinkPixelFormat_decklinkFormat12BitRGB as isize,
-    Format12BitRGBLE = sdk::_DecklinkPixelFormat_decklinkFormat12BitRGBLE as isize,
-    Format10BitRGBXLE = sdk::_DecklinkPixelFormat_decklinkFormat10BitRGBXLE as isize,
-    Format10BitRGBX = sdk::_DecklinkPixelFormat_decklinkFormat10BitRGBX as isize,
-    FormatH265 = sdk::_DecklinkPixelFormat_decklinkFormatH265 as isize,
-    FormatDNxHR = sdk::_DecklinkPixelFormat_decklinkFormatDNxHR as isize,
-    Format12BitRAWGRBG = sdk::_DecklinkPixelFormat_decklinkFormat12BitRAWGRBG as isize,
-    Format12BitRAWJPEG = sdk::_DecklinkPixelFormat_decklinkFormat12BitRAWJPEG as isize,
+    Format8BitYUV,
+    Format10BitYUV,
+    Format8BitARGB,
+    Format8BitBGRA,
+    Format10BitRGB,
+    Format12BitRGB,
+    Format12BitRGBLE,
+    Format10BitRGBXLE,
+    Format10BitRGBX,
+    FormatH265,
+    FormatDNxHR,
+    Format12BitRAWGRBG,
+    Format12BitRAWJPEG,
+
+    /// A pixel format not known to this version of the bindings, as its FourCC value
+    #[strum(disabled)]
+    Other(u32),
+}
+impl From<u32> for DecklinkPixelFormat {
+    fn from(value: u32) -> Self {
+        match value {
+            sdk::_DecklinkPixelFormat_decklinkFormat8BitYUV => Self::Format8BitYUV,
+            sdk::_DecklinkPixelFormat_decklinkFormat10BitYUV => Self::Format10BitYUV,
+            sdk::_DecklinkPixelFormat_decklinkFormat8BitARGB => Self::Format8BitARGB,
+            sdk::_DecklinkPixelFormat_decklinkFormat8BitBGRA => Self::Format8BitBGRA,
+            sdk::_DecklinkPixelFormat_decklinkFormat10BitRGB => Self::Format10BitRGB,
+            sdk::_DecklinkPixelFormat_decklinkFormat12BitRGB => Self::Format12BitRGB,
+            sdk::_DecklinkPixelFormat_decklinkFormat12BitRGBLE => Self::Format12BitRGBLE,
+            sdk::_DecklinkPixelFormat_decklinkFormat10BitRGBXLE => Self::Format10BitRGBXLE,
+            sdk::_DecklinkPixelFormat_decklinkFormat10BitRGBX => Self::Format10BitRGBX,
+            sdk::_DecklinkPixelFormat_decklinkFormatH265 => Self::FormatH265,
+            sdk::_DecklinkPixelFormat_decklinkFormatDNxHR => Self::FormatDNxHR,
+            sdk::_DecklinkPixelFormat_decklinkFormat12BitRAWGRBG => Self::Format12BitRAWGRBG,
+            sdk::_DecklinkPixelFormat_decklinkFormat12BitRAWJPEG => Self::Format12BitRAWJPEG,
+            v => Self::Other(v),
+        }
+    }
+}
+impl From<DecklinkPixelFormat> for u32 {
+    fn from(value: DecklinkPixelFormat) -> Self {
+        match value {
+            DecklinkPixelFormat::Format8BitYUV => sdk::_DecklinkPixelFormat_decklinkFormat8BitYUV,
+            DecklinkPixelFormat::Format10BitYUV => sdk::_DecklinkPixelFormat_decklinkFormat10BitYUV,
+            DecklinkPixelFormat::Format8BitARGB => sdk::_DecklinkPixelFormat_decklinkFormat8BitARGB,
+            DecklinkPixelFormat::Format8BitBGRA => sdk::_DecklinkPixelFormat_decklinkFormat8BitBGRA,
+            DecklinkPixelFormat::Format10BitRGB => sdk::_DecklinkPixelFormat_decklinkFormat10BitRGB,
+            DecklinkPixelFormat::Format12BitRGB => sdk::_DecklinkPixelFormat_decklinkFormat12BitRGB,
+            DecklinkPixelFormat::Format12BitRGBLE => {
+                sdk::_DecklinkPixelFormat_decklinkFormat12BitRGBLE
+            }
+            DecklinkPixelFormat::Format10BitRGBXLE => {
+                sdk::_DecklinkPixelFormat_decklinkFormat10BitRGBXLE
+            }
+            DecklinkPixelFormat::Format10BitRGBX => {
+                sdk::_DecklinkPixelFormat_decklinkFormat10BitRGBX
+            }
+            DecklinkPixelFormat::FormatH265 => sdk::_DecklinkPixelFormat_decklinkFormatH265,
+            DecklinkPixelFormat::FormatDNxHR => sdk::_DecklinkPixelFormat_decklinkFormatDNxHR,
+            DecklinkPixelFormat::Format12BitRAWGRBG => {
+                sdk::_DecklinkPixelFormat_decklinkFormat12BitRAWGRBG
+            }
+            DecklinkPixelFormat::Format12BitRAWJPEG => {
+                sdk::_DecklinkPixelFormat_decklinkFormat12BitRAWJPEG
+            }
+            DecklinkPixelFormat::Other(v) => v,
+        }
+    }
 }
 
 bitflags! {
     #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct DecklinkFrameFlags: u32 {
         const FLIP_VERTICAL = sdk::_DecklinkFrameFlags_decklinkFrameFlagFlipVertical;
         const CONTAINS_HDR_METADATA = sdk::_DecklinkFrameFlags_decklinkFrameContainsHDRMetadata;
@@ -43,7 +100,36 @@ pub trait DecklinkFrameBase {
     /// Get the flags of the video frame
     fn flags(&self) -> DecklinkFrameFlags;
     /// Get the pixel data of the video frame
-    fn bytes(&self) -> Result<DecklinkAlignedBytes, SdkError>;
+    fn bytes(&self) -> Result<DecklinkAlignedBytes<'_>, SdkError>;
+
+    /// Get the pixel data of the video frame, including any padding at the end of each row
+    fn data(&self) -> Result<&[u8], SdkError> {
+        Ok(self.bytes()?.0)
+    }
+    /// Iterate over the rows of the pixel data.
+    /// Each row is `row_bytes` long, so may include some padding after the pixels.
+    fn rows(&self) -> Result<FrameRows<'_>, SdkError> {
+        Ok(FrameRows::new(
+            self.data()?,
+            self.row_bytes(),
+            self.height(),
+        ))
+    }
+    /// Iterate over the rows of a `Format8BitBGRA` frame, as `width` pixels per row
+    fn as_bgra_pixels(&self) -> Result<PixelRows<'_, BgraPixel>, SdkError> {
+        if self.pixel_format() != DecklinkPixelFormat::Format8BitBGRA {
+            return Err(SdkError::INVALIDARG);
+        }
+        PixelRows::new(self.rows()?, self.width())
+    }
+    /// Iterate over the rows of a `Format10BitYUV` frame, as the groups of 6 pixels that make up each row.
+    /// When the width is not a multiple of 6, the last group of each row is partially filled.
+    fn as_v210_groups(&self) -> Result<PixelRows<'_, V210Group>, SdkError> {
+        if self.pixel_format() != DecklinkPixelFormat::Format10BitYUV {
+            return Err(SdkError::INVALIDARG);
+        }
+        PixelRows::new(self.rows()?, self.width().div_ceil(6))
+    }
 }
 pub trait DecklinkFrameBase2: DecklinkFrameBase {
     /// Get the pixel data of the video frame
@@ -56,11 +142,221 @@ pub struct DecklinkAlignedBytes<'a>(pub &'a [u8]);
 /// Decklinks require byte arrays to be aligned to 64byte boundaries
 pub type DecklinkAlignedVec = AVec<u8, ConstAlign<64>>;
 
+/// An owned buffer of pixel data, which can be handed to the driver without copying.
+/// The data must start on a 64 byte boundary, otherwise `DecklinkCustomFrame::new` fails with `INVALIDARG`.
+/// Use `Arc<DecklinkAlignedVec>` to share a buffer between several frames.
+pub trait DecklinkFrameBuffer: Send {
+    /// Get the pixel data
+    fn as_bytes(&self) -> &[u8];
+
+    /// Called once the driver has finished with the buffer, after the frame has completed.
+    /// By default this drops the buffer.
+    fn release(self: Box<Self>) {}
+}
+impl DecklinkFrameBuffer for DecklinkAlignedVec {
+    fn as_bytes(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+impl DecklinkFrameBuffer for Arc<DecklinkAlignedVec> {
+    fn as_bytes(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+/// A video frame whose pixel data is held in a `DecklinkFrameBuffer`, so that it can be output without copying
+pub struct DecklinkCustomFrame {
+    width: usize,
+    height: usize,
+    row_bytes: usize,
+    pixel_format: DecklinkPixelFormat,
+    flags: DecklinkFrameFlags,
+
+    buffer: Box<dyn DecklinkFrameBuffer>,
+}
+impl DecklinkFrameBase for DecklinkCustomFrame {
+    fn width(&self) -> usize {
+        self.width
+    }
+    fn height(&self) -> usize {
+        self.height
+    }
+    fn row_bytes(&self) -> usize {
+        self.row_bytes
+    }
+    fn pixel_format(&self) -> DecklinkPixelFormat {
+        self.pixel_format
+    }
+    fn flags(&self) -> DecklinkFrameFlags {
+        self.flags
+    }
+    fn bytes(&self) -> Result<DecklinkAlignedBytes<'_>, SdkError> {
+        Ok(DecklinkAlignedBytes(self.buffer.as_bytes()))
+    }
+}
+impl DecklinkCustomFrame {
+    /// Wrap a buffer holding `row_bytes * height` bytes of pixel data.
+    /// The buffer must start on a 64 byte boundary, and the size of the frame must be a multiple of 64 bytes.
+    pub fn new(
+        width: usize,
+        height: usize,
+        row_bytes: usize,
+        pixel_format: DecklinkPixelFormat,
+        flags: DecklinkFrameFlags,
+        buffer: Box<dyn DecklinkFrameBuffer>,
+    ) -> Result<Self, SdkError> {
+        let required_bytes = row_bytes * height;
+        let bytes = buffer.as_bytes();
+        if bytes.len() < required_bytes {
+            return Err(SdkError::INVALIDARG);
+        }
+        // Must be aligned and a multiple of 64 to be valid for avx512
+        if bytes.as_ptr() as usize % 64 != 0 || required_bytes % 64 != 0 {
+            return Err(SdkError::INVALIDARG);
+        }
+
+        Ok(Self {
+            width,
+            height,
+            row_bytes,
+            pixel_format,
+            flags,
+            buffer,
+        })
+    }
+
+    /// Take the buffer back out of the frame
+    pub fn into_buffer(self) -> Box<dyn DecklinkFrameBuffer> {
+        self.buffer
+    }
+}
+
+/// The rows of a frame, created by `DecklinkFrameBase::rows`
+#[derive(Clone)]
+pub struct FrameRows<'a> {
+    rows: std::iter::Take<std::slice::Chunks<'a, u8>>,
+}
+
+impl<'a> FrameRows<'a> {
+    fn new(data: &'a [u8], row_bytes: usize, height: usize) -> FrameRows<'a> {
+        FrameRows {
+            rows: data.chunks(row_bytes.max(1)).take(height),
+        }
+    }
+}
+
+impl<'a> Iterator for FrameRows<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.rows.next()
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.rows.size_hint()
+    }
+}
+impl ExactSizeIterator for FrameRows<'_> {}
+impl DoubleEndedIterator for FrameRows<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.rows.next_back()
+    }
+}
+
+/// A pixel of a `Format8BitBGRA` frame
+#[repr(C)]
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub struct BgraPixel {
+    pub b: u8,
+    pub g: u8,
+    pub r: u8,
+    pub a: u8,
+}
+
+/// A group of 6 pixels of a `Format10BitYUV` (v210) frame, packed as four little endian words
+#[repr(C)]
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub struct V210Group(pub [u8; 16]);
+
+impl V210Group {
+    /// The four 32bit words of the group, each holding three 10bit components
+    pub fn words(&self) -> [u32; 4] {
+        let b = &self.0;
+        [
+            u32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+            u32::from_le_bytes([b[4], b[5], b[6], b[7]]),
+            u32::from_le_bytes([b[8], b[9], b[10], b[11]]),
+            u32::from_le_bytes([b[12], b[13], b[14], b[15]]),
+        ]
+    }
+
+    /// The 12 components of the group, in the order Cb0 Y0 Cr0 Y1 Cb1 Y2 Cr1 Y3 Cb2 Y4 Cr2 Y5
+    pub fn components(&self) -> [u16; 12] {
+        let mut components = [0; 12];
+        for (i, word) in self.words().iter().enumerate() {
+            components[i * 3] = (word & 0x3ff) as u16;
+            components[i * 3 + 1] = ((word >> 10) & 0x3ff) as u16;
+            components[i * 3 + 2] = ((word >> 20) & 0x3ff) as u16;
+        }
+        components
+    }
+
+    /// The luma of the 6 pixels
+    pub fn luma(&self) -> [u16; 6] {
+        let c = self.components();
+        [c[1], c[3], c[5], c[7], c[9], c[11]]
+    }
+
+    /// The chroma (Cb, Cr) shared by each pair of pixels
+    pub fn chroma(&self) -> [(u16, u16); 3] {
+        let c = self.components();
+        [(c[0], c[2]), (c[4], c[6]), (c[8], c[10])]
+    }
+}
+
+/// The rows of a frame as typed pixels, created by `DecklinkFrameBase::as_bgra_pixels` or `DecklinkFrameBase::as_v210_groups`
+pub struct PixelRows<'a, T> {
+    rows: FrameRows<'a>,
+    count: usize,
+    pixel: PhantomData<&'a T>,
+}
+
+impl<'a, T> PixelRows<'a, T> {
+    /// `T` must be a byte array type (with an alignment of 1), and `count` of them must fit in each row
+    fn new(rows: FrameRows<'a>, count: usize) -> Result<PixelRows<'a, T>, SdkError> {
+        let row_len = count * std::mem::size_of::<T>();
+        if std::mem::align_of::<T>() != 1 || rows.clone().any(|row| row.len() < row_len) {
+            return Err(SdkError::INVALIDARG);
+        }
+        Ok(PixelRows {
+            rows,
+            count,
+            pixel: PhantomData,
+        })
+    }
+}
+
+impl<'a, T: 'a> Iterator for PixelRows<'a, T> {
+    type Item = &'a [T];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let row = self.rows.next()?;
+        // The length and alignment were checked when this was created
+        Some(unsafe { std::slice::from_raw_parts(row.as_ptr() as *const T, self.count) })
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.rows.size_hint()
+    }
+}
+impl<'a, T: 'a> ExactSizeIterator for PixelRows<'a, T> {}
+
 /// This represents a video frame that has been received from a decklink device.
 pub struct DecklinkVideoFrame {
     frame: *mut crate::sdk::cdecklink_video_frame_t,
 }
 
+// The sdk objects are reference counted and safe to use and release from any thread
+unsafe impl Send for DecklinkVideoFrame {}
+
 impl Drop for DecklinkVideoFrame {
     fn drop(&mut self) {
         if !self.frame.is_null() {
@@ -70,6 +366,12 @@ impl Drop for DecklinkVideoFrame {
     }
 }
 
+impl Clone for DecklinkVideoFrame {
+    fn clone(&self) -> Self {
+        unsafe { Self::from(self.frame) }
+    }
+}
+
 impl DecklinkFrameBase for DecklinkVideoFrame {
     /// Get the width of the video frame
     fn width(&self) -> usize {
@@ -98,7 +400,7 @@ impl DecklinkFrameBase for DecklinkVideoFrame {
 
         let format = unsafe { sdk::cdecklink_video_frame_get_pixel_format(self.frame) };
 
-        DecklinkPixelFormat::from_u32(format).unwrap_or(DecklinkPixelFormat::Format8BitYUV)
+        DecklinkPixelFormat::from(format)
     }
     /// Get the flags of the video frame
     fn flags(&self) -> DecklinkFrameFlags {
@@ -109,57 +411,143 @@ impl DecklinkFrameBase for DecklinkVideoFrame {
         DecklinkFrameFlags::from_bits_truncate(flags)
     }
 
-    fn bytes(&self) -> Result<DecklinkAlignedBytes, SdkError> {
+    fn bytes(&self) -> Result<DecklinkAlignedBytes<'_>, SdkError> {
         self.bytes_handle()
     }
 }
 
 impl DecklinkVideoFrame {
-    /// Get the pixel data of the video frame
+    /// Get a copy of the pixel data of the video frame
     pub fn bytes_to_vec(&self) -> Result<Vec<u8>, SdkError> {
-        assert!(!self.frame.is_null());
-
-        let bytes = null_mut();
-        let result = unsafe { sdk::cdecklink_video_frame_get_bytes(self.frame, bytes) };
-        SdkError::result(result)?;
-
-        assert!(!bytes.is_null());
-
-        let byte_count = self.row_bytes() * self.height();
-        let mut result = vec![0; byte_count];
-
-        unsafe { std::ptr::copy(bytes as *const u8, result.as_mut_ptr(), byte_count) };
-
-        Ok(result)
+        Ok(self.bytes_handle()?.0.to_vec())
     }
 
     /// Get the pixel data of the video frame
-    pub fn bytes_handle(&self) -> Result<DecklinkAlignedBytes, SdkError> {
+    pub fn bytes_handle(&self) -> Result<DecklinkAlignedBytes<'_>, SdkError> {
         assert!(!self.frame.is_null());
 
-        let bytes = null_mut();
-        let result = unsafe { sdk::cdecklink_video_frame_get_bytes(self.frame, bytes) };
-        SdkError::result(result)?;
+        let mut bytes = null_mut();
+        let result = unsafe { sdk::cdecklink_video_frame_get_bytes(self.frame, &mut bytes) };
+        SdkError::result::<()>(result)?;
 
-        assert!(!bytes.is_null());
+        if bytes.is_null() {
+            return Err(SdkError::FAIL);
+        }
 
         let byte_count = self.row_bytes() * self.height();
 
+        // The bytes are owned by the frame, so live for as long as the borrow of it
         let slice = unsafe { std::slice::from_raw_parts(bytes as *const u8, byte_count) };
         Ok(DecklinkAlignedBytes(slice))
     }
 
-    // /// Get the raw pointer for the wrapped frame
-    // pub(crate) unsafe fn get_cdecklink_ptr(&self) -> *mut sdk::cdecklink_video_frame_t {
-    //     self.frame
-    // }
-    /// Wrap a raw pointer
+    /// Get the raw pointer for the wrapped frame
+    pub(crate) unsafe fn get_cdecklink_ptr(&self) -> *mut sdk::cdecklink_video_frame_t {
+        self.frame
+    }
+    /// Wrap a borrowed raw pointer, taking a new reference to it
     pub(crate) unsafe fn from(ptr: *mut sdk::cdecklink_video_frame_t) -> Self {
-        sdk::cdecklink_mutable_video_frame_add_ref(ptr); // TODO - all types should do this
+        sdk::cdecklink_video_frame_add_ref(ptr);
         Self { frame: ptr }
     }
 }
 
+/// This represents a video frame that has been captured by a decklink input.
+pub struct DecklinkVideoInputFrame {
+    frame: *mut sdk::cdecklink_video_input_frame_t,
+    video_frame: DecklinkVideoFrame,
+}
+
+unsafe impl Send for DecklinkVideoInputFrame {}
+
+impl Drop for DecklinkVideoInputFrame {
+    fn drop(&mut self) {
+        if !self.frame.is_null() {
+            unsafe { sdk::cdecklink_video_input_frame_release(self.frame) };
+            self.frame = null_mut();
+        }
+    }
+}
+
+impl Clone for DecklinkVideoInputFrame {
+    fn clone(&self) -> Self {
+        unsafe { sdk::cdecklink_video_input_frame_add_ref(self.frame) };
+        Self {
+            frame: self.frame,
+            video_frame: self.video_frame.clone(),
+        }
+    }
+}
+
+impl DecklinkFrameBase for DecklinkVideoInputFrame {
+    fn width(&self) -> usize {
+        self.video_frame.width()
+    }
+    fn height(&self) -> usize {
+        self.video_frame.height()
+    }
+    fn row_bytes(&self) -> usize {
+        self.video_frame.row_bytes()
+    }
+    fn pixel_format(&self) -> DecklinkPixelFormat {
+        self.video_frame.pixel_format()
+    }
+    fn flags(&self) -> DecklinkFrameFlags {
+        self.video_frame.flags()
+    }
+    fn bytes(&self) -> Result<DecklinkAlignedBytes<'_>, SdkError> {
+        self.video_frame.bytes()
+    }
+}
+
+impl DecklinkVideoInputFrame {
+    /// Get the frame as a plain video frame
+    pub fn video_frame(&self) -> &DecklinkVideoFrame {
+        &self.video_frame
+    }
+
+    /// Get the stream time and duration of the frame, in the requested timescale
+    pub fn stream_time(&self, timescale: i64) -> Result<(i64, i64), SdkError> {
+        let mut time = 0;
+        let mut duration = 0;
+        let result = unsafe {
+            sdk::cdecklink_video_input_frame_get_stream_time(
+                self.frame,
+                &mut time,
+                &mut duration,
+                timescale,
+            )
+        };
+        SdkError::result_or(result, (time, duration))
+    }
+
+    /// Get the hardware reference time and duration at which the frame was captured, in the requested timescale
+    pub fn hardware_reference_timestamp(&self, timescale: i64) -> Result<(i64, i64), SdkError> {
+        let mut time = 0;
+        let mut duration = 0;
+        let result = unsafe {
+            sdk::cdecklink_video_input_frame_get_hardware_reference_timestamp(
+                self.frame,
+                timescale,
+                &mut time,
+                &mut duration,
+            )
+        };
+        SdkError::result_or(result, (time, duration))
+    }
+
+    /// Wrap a borrowed raw pointer, taking a new reference to it
+    pub(crate) unsafe fn from(ptr: *mut sdk::cdecklink_video_input_frame_t) -> Self {
+        sdk::cdecklink_video_input_frame_add_ref(ptr);
+        Self {
+            frame: ptr,
+            video_frame: DecklinkVideoFrame::from(sdk::cdecklink_video_input_frame_to_video_frame(
+                ptr,
+            )),
+        }
+    }
+}
+
 pub struct DecklinkVideoMutableFrame {
     width: usize,
     height: usize,
@@ -190,9 +578,9 @@ impl DecklinkFrameBase for DecklinkVideoMutableFrame {
         self.flags
     }
 
-    fn bytes(&self) -> Result<DecklinkAlignedBytes, SdkError> {
+    fn bytes(&self) -> Result<DecklinkAlignedBytes<'_>, SdkError> {
         if let Some(bytes) = &self.bytes {
-            Ok(DecklinkAlignedBytes(&bytes))
+            Ok(DecklinkAlignedBytes(bytes))
         } else {
             Err(SdkError::FALSE)
         }
@@ -225,6 +613,25 @@ impl DecklinkVideoMutableFrame {
         }
     }
 
+    /// Get the pixel data of the frame to modify it in place.
+    /// This is only offered for frames owned by Rust. The sdk frames handed out by this crate are captured
+    /// frames and completed output frames, which the driver may still be reading, so they are read only.
+    /// To modify one, copy it into a `DecklinkVideoMutableFrame` with `copy_bytes`.
+    pub fn data_mut(&mut self) -> Result<&mut [u8], SdkError> {
+        match &mut self.bytes {
+            Some(bytes) => Ok(bytes.as_mut_slice()),
+            None => Err(SdkError::FALSE),
+        }
+    }
+
+    /// Iterate over the rows of the pixel data to modify them in place.
+    /// Each row is `row_bytes` long, so may include some padding after the pixels.
+    pub fn rows_mut(&mut self) -> Result<impl Iterator<Item = &mut [u8]>, SdkError> {
+        let row_bytes = self.row_bytes.max(1);
+        let height = self.height;
+        Ok(self.data_mut()?.chunks_mut(row_bytes).take(height))
+    }
+
     pub fn set_bytes(&mut self, bytes: DecklinkAlignedVec) -> Result<(), SdkError> {
         if bytes.len() < self.row_bytes * self.height {
             Err(SdkError::INVALIDARG)
@@ -256,3 +663,206 @@ impl DecklinkVideoMutableFrame {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn pixel_format_round_trips() {
+        let mut values = Vec::new();
+        for format in DecklinkPixelFormat::iter() {
+            let value = u32::from(format);
+            assert_eq!(DecklinkPixelFormat::from(value), format, "{:?}", format);
+            assert!(!values.contains(&value), "{:?} is not unique", format);
+            values.push(value);
+        }
+
+        let unknown = u32::from_be_bytes(*b"zzzz");
+        assert_eq!(
+            DecklinkPixelFormat::from(unknown),
+            DecklinkPixelFormat::Other(unknown)
+        );
+        assert_eq!(u32::from(DecklinkPixelFormat::Other(unknown)), unknown);
+    }
+
+    fn numbered_frame(
+        width: usize,
+        height: usize,
+        row_bytes: usize,
+        format: DecklinkPixelFormat,
+    ) -> DecklinkVideoMutableFrame {
+        let mut frame = DecklinkVideoMutableFrame::create(
+            width,
+            height,
+            row_bytes,
+            format,
+            DecklinkFrameFlags::empty(),
+        );
+        let bytes: Vec<u8> = (0..row_bytes * height).map(|i| i as u8).collect();
+        frame.copy_bytes(&bytes).unwrap();
+        frame
+    }
+
+    #[test]
+    fn frame_rows_include_padding() {
+        // 2 BGRA pixels per row, padded to 12 bytes
+        let frame = numbered_frame(2, 3, 12, DecklinkPixelFormat::Format8BitBGRA);
+        let rows: Vec<&[u8]> = frame.rows().unwrap().collect();
+
+        assert_eq!(rows.len(), 3);
+        for (i, row) in rows.iter().enumerate() {
+            assert_eq!(row.len(), 12);
+            assert_eq!(row[0], (i * 12) as u8);
+        }
+        assert_eq!(frame.rows().unwrap().len(), 3);
+        assert_eq!(frame.rows().unwrap().next_back().unwrap()[0], 24);
+    }
+
+    #[test]
+    fn frame_rows_ignore_trailing_data() {
+        let data = [0u8; 40];
+        let rows = FrameRows::new(&data, 12, 3);
+        assert_eq!(rows.map(|row| row.len()).collect::<Vec<_>>(), [12, 12, 12]);
+
+        // A short buffer gives a short last row, rather than reading past the end
+        let rows = FrameRows::new(&data[..30], 12, 3);
+        assert_eq!(rows.map(|row| row.len()).collect::<Vec<_>>(), [12, 12, 6]);
+    }
+
+    #[test]
+    fn pixel_rows_skip_padding() {
+        let frame = numbered_frame(2, 3, 12, DecklinkPixelFormat::Format8BitBGRA);
+        let rows: Vec<&[BgraPixel]> = frame.as_bgra_pixels().unwrap().collect();
+
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[1].len(), 2);
+        assert_eq!(
+            rows[1][1],
+            BgraPixel {
+                b: 16,
+                g: 17,
+                r: 18,
+                a: 19
+            }
+        );
+    }
+
+    #[test]
+    fn pixel_rows_check_length() {
+        let data = [0u8; 24];
+        assert!(PixelRows::<BgraPixel>::new(FrameRows::new(&data, 8, 3), 2).is_ok());
+        assert!(matches!(
+            PixelRows::<BgraPixel>::new(FrameRows::new(&data, 8, 3), 3),
+            Err(SdkError::INVALIDARG)
+        ));
+        // The last row is short
+        assert!(matches!(
+            PixelRows::<BgraPixel>::new(FrameRows::new(&data[..20], 8, 3), 2),
+            Err(SdkError::INVALIDARG)
+        ));
+        // Only types with an alignment of 1 can be viewed over the bytes
+        assert!(matches!(
+            PixelRows::<u32>::new(FrameRows::new(&data, 8, 3), 1),
+            Err(SdkError::INVALIDARG)
+        ));
+    }
+
+    #[test]
+    fn pixel_rows_check_format() {
+        let frame = numbered_frame(2, 3, 12, DecklinkPixelFormat::Format8BitBGRA);
+        assert!(matches!(frame.as_v210_groups(), Err(SdkError::INVALIDARG)));
+
+        // 7 pixels need 2 groups, which do not fit in a row of 16 bytes
+        let frame = numbered_frame(7, 2, 16, DecklinkPixelFormat::Format10BitYUV);
+        assert!(matches!(frame.as_v210_groups(), Err(SdkError::INVALIDARG)));
+        assert!(matches!(frame.as_bgra_pixels(), Err(SdkError::INVALIDARG)));
+    }
+
+    fn pack(components: [u16; 12]) -> V210Group {
+        let mut group = V210Group::default();
+        for (i, word) in components.chunks(3).enumerate() {
+            let word = u32::from(word[0]) | (u32::from(word[1]) << 10) | (u32::from(word[2]) << 20);
+            group.0[i * 4..i * 4 + 4].copy_from_slice(&word.to_le_bytes());
+        }
+        group
+    }
+
+    #[test]
+    fn v210_group_components() {
+        let components = [64, 940, 512, 1023, 0, 100, 200, 300, 400, 500, 600, 700];
+        let group = pack(components);
+
+        assert_eq!(group.components(), components);
+        assert_eq!(group.luma(), [940, 1023, 100, 300, 500, 700]);
+        assert_eq!(group.chroma(), [(64, 512), (0, 200), (400, 600)]);
+    }
+
+    #[test]
+    fn v210_group_ignores_padding_bits() {
+        // The top 2 bits of each word are unused
+        let group = V210Group([0xff; 16]);
+        assert_eq!(group.components(), [0x3ff; 12]);
+        assert_eq!(group.words(), [u32::MAX; 4]);
+    }
+
+    #[test]
+    fn v210_groups_of_frame() {
+        // 6 pixels wide, so a single group per row
+        let mut frame = DecklinkVideoMutableFrame::create(
+            6,
+            2,
+            16,
+            DecklinkPixelFormat::Format10BitYUV,
+            DecklinkFrameFlags::empty(),
+        );
+        let first = pack([1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]);
+        let second = pack([12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1]);
+        frame.copy_bytes(&[first.0, second.0].concat()).unwrap();
+
+        let rows: Vec<&[V210Group]> = frame.as_v210_groups().unwrap().collect();
+        assert_eq!(rows, [[first], [second]]);
+        assert_eq!(rows[1][0].luma(), [11, 9, 7, 5, 3, 1]);
+    }
+
+    /// A buffer whose data starts one byte into an aligned allocation
+    struct Misaligned(DecklinkAlignedVec);
+    impl DecklinkFrameBuffer for Misaligned {
+        fn as_bytes(&self) -> &[u8] {
+            &self.0[1..]
+        }
+    }
+
+    #[test]
+    fn custom_frame_checks_alignment() {
+        let custom = |buffer: Box<dyn DecklinkFrameBuffer>, row_bytes| {
+            DecklinkCustomFrame::new(
+                16,
+                2,
+                row_bytes,
+                DecklinkPixelFormat::Format8BitBGRA,
+                DecklinkFrameFlags::empty(),
+                buffer,
+            )
+        };
+        let aligned = || DecklinkAlignedVec::from_slice(64, &[0; 256]);
+
+        assert!(custom(Box::new(aligned()), 64).is_ok());
+        assert!(custom(Box::new(Arc::new(aligned())), 64).is_ok());
+        assert!(matches!(
+            custom(Box::new(Misaligned(aligned())), 64),
+            Err(SdkError::INVALIDARG)
+        ));
+        // Too short
+        assert!(matches!(
+            custom(Box::new(aligned()), 192),
+            Err(SdkError::INVALIDARG)
+        ));
+        // Not a multiple of 64 bytes in total
+        assert!(matches!(
+            custom(Box::new(aligned()), 72),
+            Err(SdkError::INVALIDARG)
+        ));
+    }
+}
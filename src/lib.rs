@@ -16,9 +16,11 @@ extern crate strum_macros;
 // #[link(name = "decklink_c", kind = "static")]
 mod sdk;
 
+pub mod callback;
 pub mod connectors;
 pub mod device;
 pub mod display_mode;
+pub mod edid;
 pub mod frame;
 mod util;
 